@@ -0,0 +1,46 @@
+use tokio::{fs, task};
+
+use crate::{build_package_info, locate_package_json, Options, PackageInfo, PackageJSON, Required};
+
+/// `package.json` files larger than this are parsed on a blocking thread.
+const BLOCKING_PARSE_THRESHOLD: usize = 64 * 1024;
+
+/// Get npm package info like [`crate::get_package_info`], reading the `package.json` with
+/// `tokio::fs`
+///
+/// Large files are parsed with `spawn_blocking`, so many packages can be read concurrently
+/// without stalling the runtime. Requires the `tokio` feature.
+///
+/// # Exmaple
+/// ```
+/// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+/// use npm_pkg::{get_package_info, get_package_info_async, Options};
+///
+/// let pkg = get_package_info_async("consola", Options::default()).await;
+///
+/// assert_eq!(pkg, get_package_info("consola", Options::default()));
+/// # });
+/// ```
+pub async fn get_package_info_async(name: &str, options: Options<'_>) -> Option<PackageInfo> {
+    let package_json_path = locate_package_json(name, &options).ok()?;
+    let json = fs::read_to_string(&package_json_path).await.ok()?;
+    let (package_json, json) = match json.len() < BLOCKING_PARSE_THRESHOLD {
+        true => (serde_json::from_str::<PackageJSON>(&json).ok()?, json),
+        false => task::spawn_blocking(move || {
+            serde_json::from_str::<PackageJSON>(&json).map(|package_json| (package_json, json))
+        })
+        .await
+        .ok()?
+        .ok()?,
+    };
+
+    build_package_info(
+        name,
+        package_json_path,
+        package_json,
+        json,
+        &options,
+        Required::VersionAndEntry,
+    )
+    .ok()
+}
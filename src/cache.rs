@@ -0,0 +1,69 @@
+use std::{collections::HashMap, path::PathBuf};
+
+use crate::{get_package_info_for, lookup_dir, Options, PackageInfo};
+
+/// Packages loaded by [`PackageCache::get_or_insert`], keyed by the directory lookups start
+/// from and the name
+///
+/// Only the directory lookups start from is part of the key, so a cache should be used
+/// with a single set of the other [`Options`]. The cache is `Send + Sync` and can be shared
+/// behind an `Arc<Mutex<_>>`.
+///
+/// # Exmaple
+/// ```
+/// use npm_pkg::{Options, PackageCache};
+///
+/// let mut cache = PackageCache::new();
+/// let options = Options::default();
+///
+/// assert_eq!(cache.get_or_insert("consola", &options).map(|pkg| pkg.name.as_str()), Some("consola"));
+/// assert_eq!(cache.len(), 1);
+///
+/// cache.remove("consola");
+/// assert!(cache.is_empty());
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct PackageCache {
+    packages: HashMap<(PathBuf, String), PackageInfo>,
+}
+
+impl PackageCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get package `name` like [`crate::get_package_info`], only reading it from disk when
+    /// it is not cached yet
+    ///
+    /// Packages that cannot be loaded are not cached, so they are looked up again next
+    /// time.
+    pub fn get_or_insert(&mut self, name: &str, options: &Options) -> Option<&PackageInfo> {
+        let key = (lookup_dir(options), name.to_string());
+
+        if !self.packages.contains_key(&key) {
+            let pkg_info = get_package_info_for(name, options)?;
+
+            self.packages.insert(key.clone(), pkg_info);
+        }
+
+        self.packages.get(&key)
+    }
+
+    /// Forget package `name`, for every `cwd`.
+    pub fn remove(&mut self, name: &str) {
+        self.packages.retain(|(_, cached), _| cached != name);
+    }
+
+    /// Forget every package.
+    pub fn clear(&mut self) {
+        self.packages.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.packages.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.packages.is_empty()
+    }
+}
@@ -0,0 +1,96 @@
+use std::{error::Error, fmt, io, path::PathBuf};
+
+/// Why a package could not be resolved or loaded
+#[derive(Debug)]
+pub enum PackageError {
+    /// The name is not a valid npm package name.
+    InvalidName { name: String },
+    /// The package is not installed where it is looked for, or is not allowed.
+    NotFound {
+        name: String,
+        /// The `node_modules` directories looked in, nearest first; empty when the name is
+        /// not allowed by [`crate::Options`].
+        searched: Vec<PathBuf>,
+    },
+    /// The `package.json` could not be read.
+    ReadFailed { path: PathBuf, source: io::Error },
+    /// The `package.json` is not valid JSON or does not match the expected shape.
+    ParseFailed {
+        path: PathBuf,
+        source: serde_json::Error,
+    },
+    /// A field of the `package.json` does not have the expected shape.
+    InvalidField {
+        path: PathBuf,
+        field: String,
+        source: serde_json::Error,
+    },
+    /// The `package.json` has no `version`.
+    MissingVersion { path: PathBuf },
+    /// No entry file could be determined for the package.
+    MissingEntry { path: PathBuf },
+    /// The package's `exports` do not export the subpath for the conditions.
+    SubpathNotExported { name: String, subpath: String },
+}
+
+impl fmt::Display for PackageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PackageError::InvalidName { name } => write!(f, "Invalid package name {:?}", name),
+            PackageError::NotFound { name, searched } => {
+                write!(f, "Cannot find package {:?} in {:?}", name, searched)
+            }
+            PackageError::ReadFailed { path, source } => {
+                write!(f, "Cannot read {:?}: {}", path, source)
+            }
+            PackageError::ParseFailed { path, source } => {
+                write!(f, "Cannot parse {:?}: {}", path, source)
+            }
+            PackageError::InvalidField {
+                path,
+                field,
+                source,
+            } => write!(f, "Invalid `{}` in {:?}: {}", field, path, source),
+            PackageError::MissingVersion { path } => write!(f, "No version in {:?}", path),
+            PackageError::MissingEntry { path } => {
+                write!(f, "Cannot find an entry for {:?}", path)
+            }
+            PackageError::SubpathNotExported { name, subpath } => write!(
+                f,
+                "Package subpath '{}' is not defined by \"exports\" in {}",
+                subpath, name
+            ),
+        }
+    }
+}
+
+impl Error for PackageError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            PackageError::ReadFailed { source, .. } => Some(source),
+            PackageError::ParseFailed { source, .. } => Some(source),
+            PackageError::InvalidField { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+/// Workspace packages that depend on each other in a cycle
+#[derive(Clone, Debug, PartialEq)]
+pub struct CycleError {
+    /// The packages forming the cycle, each depending on the next and the last on the first.
+    pub packages: Vec<String>,
+}
+
+impl fmt::Display for CycleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Dependency cycle: {}", self.packages.join(" -> "))?;
+
+        match self.packages.first() {
+            Some(first) => write!(f, " -> {}", first),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Error for CycleError {}
@@ -0,0 +1,135 @@
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    exports::{resolve_manifest_export, to_export_subpath},
+    locations_from, resolve_main, start_dir, try_load_package_info_with, Options, PackageInfo,
+    Required,
+};
+
+/// Why a specifier resolves to the package copy and file it does
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+pub struct ResolutionExplanation {
+    /// The package part of the specifier, e.g. `@scope/pkg` for `@scope/pkg/utils`.
+    pub name: String,
+    /// The subpath looked up in `exports`, `"."` for the package root.
+    pub subpath: String,
+    /// The copy that wins, `None` when the package cannot be resolved.
+    pub package: Option<PackageInfo>,
+    /// The `node_modules` directory the winning copy was found in.
+    pub node_modules: Option<PathBuf>,
+    /// The `exports` key the subpath matched, e.g. `"./*"`; `None` when the package has no
+    /// `exports` or does not export the subpath.
+    pub matched_key: Option<String>,
+    /// The conditions leading to the `exports` target, outermost first.
+    pub matched_conditions: Vec<String>,
+    /// The file the specifier resolves to.
+    pub file: Option<PathBuf>,
+    /// The other copies of the package, which lose against the winning one, in lookup
+    /// order.
+    pub shadowed: Vec<PackageInfo>,
+}
+
+/// Explain how a bare `specifier` such as `consola/utils` is resolved from the directory
+/// `from`
+///
+/// The package is looked up like `get_package_info` with [`Options::from`] set to `from`,
+/// which takes precedence over the one in `options`, and the subpath like
+/// [`crate::get_package_subpath`], so `./package.json` resolves to the manifest unless
+/// `exports` declares it. Without `exports`, the subpath is resolved relative to the
+/// package root like `require` does, and the root to the package entry. A package without
+/// a runtime entry is still explained, e.g. one that only exports subpaths.
+///
+/// # Exmaple
+/// ```
+/// use std::path::Path;
+/// use npm_pkg::{explain_resolution, Options};
+///
+/// let explanation = explain_resolution("consola/utils", Path::new("."), &Options::default());
+///
+/// assert_eq!(explanation.package.map(|pkg| pkg.version).as_deref(), Some("3.2.3"));
+/// assert_eq!(explanation.matched_key.as_deref(), Some("./utils"));
+/// assert_eq!(explanation.matched_conditions, vec!["import"]);
+/// assert!(explanation.shadowed.is_empty());
+/// ```
+pub fn explain_resolution(
+    specifier: &str,
+    from: &Path,
+    options: &Options,
+) -> ResolutionExplanation {
+    let (name, subpath) = split_specifier(specifier);
+    let mut explanation = ResolutionExplanation {
+        name: name.to_string(),
+        subpath: to_export_subpath(subpath),
+        ..Default::default()
+    };
+    let load = |dir: &PathBuf| {
+        try_load_package_info_with(name, dir.join("package.json"), options, Required::Version).ok()
+    };
+    let locations = locations_from(&start_dir(from), name, options);
+    let Some((winner, shadowed)) = locations.split_first() else {
+        return explanation;
+    };
+    let Some(pkg_info) = load(winner) else {
+        return explanation;
+    };
+
+    explanation.node_modules = winner
+        .ancestors()
+        .nth(name.split('/').count())
+        .map(Path::to_path_buf);
+    explanation.shadowed = shadowed.iter().filter_map(load).collect();
+
+    let conditions = options.condition_list();
+    let resolved = resolve_manifest_export(
+        &pkg_info.root_path,
+        &pkg_info.package_json,
+        &explanation.subpath,
+        &conditions,
+    );
+
+    match resolved {
+        Some((file, export_match)) => {
+            explanation.file = Some(file);
+
+            if let Some(export_match) = export_match {
+                explanation.matched_key = Some(export_match.key.to_string());
+                explanation.matched_conditions = export_match
+                    .conditions
+                    .iter()
+                    .map(|condition| condition.to_string())
+                    .collect();
+            }
+        }
+        None if pkg_info.package_json.exports.is_some() => {}
+        None if explanation.subpath == "." => {
+            explanation.file =
+                Some(pkg_info.package_entry.clone()).filter(|entry| !entry.as_os_str().is_empty());
+        }
+        None => {
+            explanation.file = resolve_main(
+                &pkg_info.root_path,
+                subpath,
+                pkg_info.package_json.is_module(),
+            );
+        }
+    }
+
+    explanation.package = Some(pkg_info);
+    explanation
+}
+
+/// Split a bare specifier into the package name and the part after it, e.g. `utils` for
+/// `consola/utils`; the part is empty for the package root.
+fn split_specifier(specifier: &str) -> (&str, &str) {
+    let name_segments = match specifier.starts_with('@') {
+        true => 2,
+        false => 1,
+    };
+
+    match specifier.match_indices('/').nth(name_segments - 1) {
+        Some((index, _)) => (&specifier[..index], &specifier[index + 1..]),
+        None => (specifier, ""),
+    }
+}
@@ -0,0 +1,576 @@
+use std::path::{Path, PathBuf};
+
+use indexmap::IndexMap;
+
+use crate::{
+    get_package_json, get_package_json_path, join_manifest_path, locate_package_json,
+    probe_extensions, read_package_json, ExportValue, Options, PackageError, PackageJSON,
+};
+
+impl ExportValue {
+    /// Resolve `subpath` (`"."` or `"./feature"`) of an `exports` field under `conditions`
+    ///
+    /// Subpath keys are only recognized at the top level; below that every object is a set
+    /// of conditions, where the first key in `conditions` or `"default"`, which always
+    /// matches, wins in the order the keys are written. Targets must start with
+    /// `./`, and arrays fall back to their next item when one does not resolve. Pattern keys
+    /// such as `"./features/*"` substitute the matched segment into the target.
+    ///
+    /// # Exmaple
+    /// ```
+    /// use npm_pkg::ExportValue;
+    ///
+    /// let exports: ExportValue = serde_json::from_str(r#"{
+    ///     ".": "./index.js",
+    ///     "./feature.js": { "node": "./feature-node.js", "default": "./feature.js" }
+    /// }"#).unwrap();
+    ///
+    /// assert_eq!(exports.resolve("./feature.js", &["node"]).as_deref(), Some("./feature-node.js"));
+    /// assert_eq!(exports.resolve("./feature.js", &["browser", "default"]).as_deref(), Some("./feature.js"));
+    /// assert_eq!(exports.resolve("./missing.js", &["default"]), None);
+    /// ```
+    pub fn resolve(&self, subpath: &str, conditions: &[&str]) -> Option<String> {
+        resolve_export(self, subpath, conditions)
+    }
+}
+
+/// Get the file `subpath` (`"."` or `"./feature"`) of package `name` is exported as
+///
+/// `exports` is matched with `conditions`, or with [`Options::conditions`] when
+/// `conditions` is empty, so one set of options can serve several targets. Files below a
+/// legacy directory export such as `"./lib/": "./src/lib/"` are probed for an extension
+/// when the exact file does not exist.
+///
+/// `"./package.json"` always resolves to the manifest, however restrictive `exports` is,
+/// unless `exports` declares that key itself, e.g. as `"./package.json": null` to block it.
+///
+/// # Exmaple
+/// ```
+/// use std::env::current_dir;
+/// use npm_pkg::{get_package_export, Options};
+///
+/// let options = Options::default();
+/// let root = current_dir().unwrap().join("node_modules/consola");
+///
+/// assert_eq!(get_package_export("consola", "./utils", &[], &options), Some(root.join("dist/utils.mjs")));
+/// assert_eq!(get_package_export("consola", "./utils", &["require"], &options), Some(root.join("dist/utils.cjs")));
+/// ```
+pub fn get_package_export(
+    name: &str,
+    subpath: &str,
+    conditions: &[&str],
+    options: &Options,
+) -> Option<PathBuf> {
+    try_get_package_export(name, subpath, conditions, options).ok()
+}
+
+/// Get the file `subpath` of package `name` is exported as like [`get_package_export`],
+/// telling why it could not be resolved
+///
+/// Only the manifest is read, so a package exporting subpaths without a root entry
+/// resolves too. Fails with [`PackageError::SubpathNotExported`] when the package is found
+/// but its `exports` do not export `subpath` under the conditions, or it has no `exports`
+/// at all.
+///
+/// # Exmaple
+/// ```
+/// use npm_pkg::{try_get_package_export, Options, PackageError};
+///
+/// let error = try_get_package_export("consola", "./missing", &[], &Options::default()).unwrap_err();
+///
+/// assert!(matches!(error, PackageError::SubpathNotExported { .. }));
+/// assert_eq!(error.to_string(), r#"Package subpath './missing' is not defined by "exports" in consola"#);
+/// ```
+pub fn try_get_package_export(
+    name: &str,
+    subpath: &str,
+    conditions: &[&str],
+    options: &Options,
+) -> Result<PathBuf, PackageError> {
+    let package_json_path = locate_package_json(name, options)?;
+    let pkg_json = read_package_json(&package_json_path)?;
+    let root = package_json_path.parent().unwrap();
+    let default_conditions = options.condition_list();
+    let conditions = match conditions.is_empty() {
+        true => &default_conditions,
+        false => conditions,
+    };
+
+    resolve_manifest_export(root, &pkg_json, subpath, conditions)
+        .map(|(file, _)| file)
+        .ok_or_else(|| PackageError::SubpathNotExported {
+            name: name.to_string(),
+            subpath: subpath.to_string(),
+        })
+}
+
+/// Resolve an internal `#` specifier such as `"#internal/foo"` from the `imports` field of
+/// `pkg` under `conditions`
+///
+/// Keys and conditions are matched like for `exports`, `*` patterns included. Unlike
+/// exports, a target may also be a bare package specifier such as `"lodash"`, which is
+/// returned as is.
+///
+/// # Exmaple
+/// ```
+/// use npm_pkg::{resolve_import, PackageJSON};
+///
+/// let pkg: PackageJSON = serde_json::from_str(r##"{
+///     "imports": { "#dep": { "node": "dep-node", "default": "./dep.js" } }
+/// }"##).unwrap();
+///
+/// assert_eq!(resolve_import(&pkg, "#dep", &[String::from("node")]).as_deref(), Some("dep-node"));
+/// assert_eq!(resolve_import(&pkg, "#missing", &[String::from("node")]), None);
+/// ```
+pub fn resolve_import(pkg: &PackageJSON, specifier: &str, conditions: &[String]) -> Option<String> {
+    if !specifier.starts_with('#') || specifier == "#" || specifier.starts_with("#/") {
+        return None;
+    }
+
+    let imports = pkg.imports.as_ref()?;
+    let (value, captured) = match imports.get(specifier) {
+        Some(value) => (value, None),
+        None => {
+            let (_, value, captured) = match_pattern(imports, specifier)?;
+
+            (value, captured)
+        }
+    };
+    let conditions: Vec<&str> = conditions.iter().map(String::as_str).collect();
+    let target = resolve_target(value, &conditions, &is_import_target)?;
+
+    match captured {
+        Some(Capture::Pattern(captured)) => Some(target.replace('*', captured)),
+        Some(Capture::Directory(captured)) => target
+            .ends_with('/')
+            .then(|| format!("{}{}", target, captured)),
+        None => Some(target.to_string()),
+    }
+}
+
+/// The part of a subpath matched by a pattern or directory key.
+#[derive(Clone, Copy)]
+pub(crate) enum Capture<'a> {
+    /// Matched by the `*` of a key such as `"./features/*"`.
+    Pattern(&'a str),
+    /// What follows a legacy directory key such as `"./lib/"`.
+    Directory(&'a str),
+}
+
+/// Get the file a deep import such as `consola/utils` resolves to, given the part after the
+/// package name (`"utils"`)
+///
+/// The subpath is looked up as `"./utils"` in `exports` and matched with
+/// [`Options::conditions`]; when `exports` is a bare string only the root is exported.
+///
+/// # Exmaple
+/// ```
+/// use std::env::current_dir;
+/// use npm_pkg::{get_package_subpath, Options};
+///
+/// let root = current_dir().unwrap().join("node_modules/consola");
+///
+/// assert_eq!(get_package_subpath("consola", "utils", &Options::default()), Some(root.join("dist/utils.mjs")));
+/// assert_eq!(get_package_subpath("consola", "missing", &Options::default()), None);
+/// ```
+pub fn get_package_subpath(name: &str, subpath: &str, options: &Options) -> Option<PathBuf> {
+    get_package_export(name, &to_export_subpath(subpath), &[], options)
+}
+
+/// Get every condition path under which `subpath` of package `name` resolves to an existing
+/// file, sorted
+///
+/// `subpath` is given like for [`get_package_subpath`]. A target reached without any
+/// condition yields an empty path; an unresolvable subpath yields no paths at all.
+///
+/// # Exmaple
+/// ```
+/// use npm_pkg::{required_conditions_for, Options};
+///
+/// let paths = required_conditions_for("consola", "core", &Options::default());
+///
+/// assert_eq!(paths, vec![vec!["import"], vec!["require"], vec!["types"]]);
+/// ```
+pub fn required_conditions_for(name: &str, subpath: &str, options: &Options) -> Vec<Vec<String>> {
+    let Some(package_json_path) = get_package_json_path(name, options) else {
+        return vec![];
+    };
+    let Some(exports) = get_package_json(&package_json_path).and_then(|pkg| pkg.exports) else {
+        return vec![];
+    };
+    let root = package_json_path.parent().unwrap();
+    let subpath = to_export_subpath(subpath);
+    let Some((value, captured)) = get_export(&exports, &subpath) else {
+        return vec![];
+    };
+    let mut paths = vec![];
+
+    collect_condition_paths(value, &mut vec![], &mut |conditions, target| {
+        let target = match captured {
+            Some(Capture::Pattern(captured)) => target.replace('*', captured),
+            Some(Capture::Directory(captured)) => format!("{}{}", target, captured),
+            None => target.to_string(),
+        };
+
+        if is_relative_target(&target) && join_manifest_path(root, &target).is_file() {
+            paths.push(
+                conditions
+                    .iter()
+                    .map(|condition| condition.to_string())
+                    .collect(),
+            );
+        }
+    });
+
+    paths.sort();
+    paths.dedup();
+    paths
+}
+
+fn collect_condition_paths<'a>(
+    value: &'a ExportValue,
+    conditions: &mut Vec<&'a str>,
+    found: &mut impl FnMut(&[&str], &str),
+) {
+    match value {
+        ExportValue::String(target) => found(conditions, target),
+        ExportValue::Null => {}
+        ExportValue::Array(values) => {
+            for value in values {
+                collect_condition_paths(value, conditions, found);
+            }
+        }
+        ExportValue::HashMap(map) => {
+            for (condition, value) in map {
+                conditions.push(condition);
+                collect_condition_paths(value, conditions, found);
+                conditions.pop();
+            }
+        }
+    }
+}
+
+/// Turn the part of a specifier after the package name into an `exports` key.
+pub(crate) fn to_export_subpath(subpath: &str) -> String {
+    match subpath {
+        "" | "." => String::from("."),
+        subpath if subpath.starts_with("./") => subpath.to_string(),
+        subpath => format!("./{}", subpath),
+    }
+}
+
+/// Get the export value for `subpath` (`"."` for the package root), along with the part
+/// of `subpath` matched by a pattern or directory key
+///
+/// Exact keys win over patterns and directories, which are ordered like Node does: the
+/// longest prefix before the `*` (or the whole directory key), then the longest key. A
+/// `"./*"` catch-all is therefore only used when nothing more specific matches.
+pub(crate) fn get_export<'a, 'b>(
+    exports: &'a ExportValue,
+    subpath: &'b str,
+) -> Option<(&'a ExportValue, Option<Capture<'b>>)> {
+    get_keyed_export(exports, subpath).map(|(_, value, captured)| (value, captured))
+}
+
+/// Like [`get_export`], also returning the key that matched; `"."` for the sugar forms.
+fn get_keyed_export<'a, 'b>(
+    exports: &'a ExportValue,
+    subpath: &'b str,
+) -> Option<(&'a str, &'a ExportValue, Option<Capture<'b>>)> {
+    match exports {
+        ExportValue::HashMap(map) => match map_kind(map)? {
+            MapKind::Subpaths => match map.get_key_value(subpath) {
+                Some((key, value)) => Some((key, value, None)),
+                None => match_pattern(map, subpath),
+            },
+            MapKind::Conditions => (subpath == ".").then_some((".", exports, None)),
+        },
+        ExportValue::Null => None,
+        // A string or an array is sugar for the root export.
+        _ => (subpath == ".").then_some((".", exports, None)),
+    }
+}
+
+fn match_pattern<'a, 'b>(
+    map: &'a IndexMap<String, ExportValue>,
+    subpath: &'b str,
+) -> Option<(&'a str, &'a ExportValue, Option<Capture<'b>>)> {
+    map.iter()
+        .filter_map(|(key, value)| {
+            let Some((prefix, suffix)) = key.split_once('*') else {
+                let captured = subpath
+                    .strip_prefix(key.as_str())
+                    .filter(|_| key.ends_with('/'))?;
+
+                return Some((key, key.len(), value, Capture::Directory(captured)));
+            };
+
+            // Like Node, the `*` has to match at least one character.
+            if suffix.contains('*') || subpath.len() < key.len() {
+                return None;
+            }
+
+            let captured = subpath.strip_prefix(prefix)?.strip_suffix(suffix)?;
+
+            Some((key, prefix.len(), value, Capture::Pattern(captured)))
+        })
+        .max_by_key(|(key, prefix_len, _, _)| (*prefix_len, key.len()))
+        .filter(|(_, _, _, captured)| !captured.text().split('/').any(|segment| segment == ".."))
+        .map(|(key, _, value, captured)| (key.as_str(), value, Some(captured)))
+}
+
+impl Capture<'_> {
+    fn text(&self) -> &str {
+        match self {
+            Capture::Pattern(captured) | Capture::Directory(captured) => captured,
+        }
+    }
+}
+
+/// Resolve an export value to its target using, at each level of nesting, the first key
+/// of a conditions object that is `"default"` or in `conditions`, like Node does.
+pub(crate) fn resolve_target<'a>(
+    value: &'a ExportValue,
+    conditions: &[&str],
+    is_valid_target: &impl Fn(&str) -> bool,
+) -> Option<&'a str> {
+    resolve_target_via(value, conditions, is_valid_target, &mut vec![])
+}
+
+/// Like [`resolve_target`], pushing the conditions leading to the target onto `path`.
+fn resolve_target_via<'a>(
+    value: &'a ExportValue,
+    conditions: &[&str],
+    is_valid_target: &impl Fn(&str) -> bool,
+    path: &mut Vec<&'a str>,
+) -> Option<&'a str> {
+    match value {
+        ExportValue::String(target) => is_valid_target(target).then_some(target),
+        ExportValue::Null => None,
+        ExportValue::Array(fallbacks) => fallbacks
+            .iter()
+            .find_map(|fallback| resolve_target_via(fallback, conditions, is_valid_target, path)),
+        ExportValue::HashMap(map) => map
+            .iter()
+            .filter(|(key, _)| !key.starts_with('.'))
+            .filter(|(key, _)| *key == "default" || conditions.contains(&key.as_str()))
+            .find_map(|(condition, value)| {
+                path.push(condition);
+
+                let target = resolve_target_via(value, conditions, is_valid_target, path);
+
+                if target.is_none() {
+                    path.pop();
+                }
+
+                target
+            }),
+    }
+}
+
+/// Resolve the root export to the first target under `conditions` that passes
+/// `is_valid_target`, skipping the others, e.g. runtime files when looking for declarations.
+pub(crate) fn resolve_root_target<'a>(
+    exports: &'a ExportValue,
+    conditions: &[&str],
+    is_valid_target: &impl Fn(&str) -> bool,
+) -> Option<&'a str> {
+    let (value, _) = get_export(exports, ".")?;
+
+    resolve_target(value, conditions, &|target| {
+        is_relative_target(target) && is_valid_target(target)
+    })
+}
+
+/// Resolve `subpath` of an `exports` field under `conditions`, substituting the segment
+/// matched by a pattern key into the target.
+pub(crate) fn resolve_export(
+    exports: &ExportValue,
+    subpath: &str,
+    conditions: &[&str],
+) -> Option<String> {
+    resolve_export_match(exports, subpath, conditions).map(|export_match| export_match.target)
+}
+
+/// How a subpath resolved through `exports`
+pub(crate) struct ExportMatch<'a> {
+    /// The key that matched, e.g. `"./*"`.
+    pub(crate) key: &'a str,
+    /// The conditions leading to the target, outermost first.
+    pub(crate) conditions: Vec<&'a str>,
+    /// The target, with the part of the subpath matched by a pattern or directory key
+    /// substituted.
+    pub(crate) target: String,
+    /// Whether the target is a file below a directory mounted by a legacy
+    /// `"./dir/": "./target/"` key.
+    mounted: bool,
+}
+
+/// Resolve `subpath` of the package in `root` from its manifest alone, returning the file
+/// and the `exports` match leading to it
+///
+/// `./package.json` is always exported unless `exports` declares it, as the manifest
+/// itself and without a match.
+pub(crate) fn resolve_manifest_export<'a>(
+    root: &Path,
+    pkg_json: &'a PackageJSON,
+    subpath: &str,
+    conditions: &[&str],
+) -> Option<(PathBuf, Option<ExportMatch<'a>>)> {
+    let exports = pkg_json.exports.as_ref();
+
+    if subpath == "./package.json"
+        && !exports.is_some_and(|exports| declares_subpath(exports, subpath))
+    {
+        return Some((root.join("package.json"), None));
+    }
+
+    let export_match = resolve_export_match(exports?, subpath, conditions)?;
+
+    Some((
+        export_file(root, pkg_json, &export_match),
+        Some(export_match),
+    ))
+}
+
+/// Like [`resolve_export`], also telling the key and the conditions that matched.
+pub(crate) fn resolve_export_match<'a>(
+    exports: &'a ExportValue,
+    subpath: &str,
+    conditions: &[&str],
+) -> Option<ExportMatch<'a>> {
+    let (key, value, captured) = get_keyed_export(exports, subpath)?;
+    let mut path = vec![];
+    let target = resolve_target_via(value, conditions, &is_relative_target, &mut path)?;
+    let (target, mounted) = match captured {
+        Some(Capture::Pattern(captured)) => (target.replace('*', captured), false),
+        Some(Capture::Directory(captured)) if target.ends_with('/') => {
+            (format!("{}{}", target, captured), true)
+        }
+        Some(Capture::Directory(_)) => return None,
+        None => (target.to_string(), false),
+    };
+
+    Some(ExportMatch {
+        key,
+        conditions: path,
+        target,
+        mounted,
+    })
+}
+
+/// Get the file an export match points to, probing extensions below a mounted directory.
+pub(crate) fn export_file(
+    root: &Path,
+    pkg_json: &PackageJSON,
+    export_match: &ExportMatch,
+) -> PathBuf {
+    let path = join_manifest_path(root, &export_match.target);
+
+    if export_match.mounted && !path.is_file() {
+        return probe_extensions(&path, pkg_json.is_module()).unwrap_or(path);
+    }
+
+    path
+}
+
+/// Whether `exports` has an exact `subpath` key, patterns and directories aside.
+fn declares_subpath(exports: &ExportValue, subpath: &str) -> bool {
+    match exports {
+        ExportValue::HashMap(map) => {
+            matches!(map_kind(map), Some(MapKind::Subpaths)) && map.contains_key(subpath)
+        }
+        _ => false,
+    }
+}
+
+/// The subpaths an `exports` field declares, sorted; `"."` for the sugar forms.
+pub(crate) fn subpaths(exports: &ExportValue) -> Vec<String> {
+    match exports {
+        ExportValue::HashMap(map) if matches!(map_kind(map), Some(MapKind::Subpaths)) => {
+            let mut subpaths: Vec<String> = map.keys().cloned().collect();
+
+            subpaths.sort();
+            subpaths
+        }
+        ExportValue::Null => vec![],
+        _ => vec![String::from(".")],
+    }
+}
+
+/// Every target in an export value with the path of conditions leading to it, joined with
+/// `.`; targets not under any condition are reported under `default`.
+pub(crate) fn condition_targets(value: &ExportValue) -> Vec<(String, &str)> {
+    let mut targets = vec![];
+
+    collect_condition_targets(value, &mut vec![], &mut targets);
+    targets
+}
+
+fn collect_condition_targets<'a>(
+    value: &'a ExportValue,
+    conditions: &mut Vec<&'a str>,
+    targets: &mut Vec<(String, &'a str)>,
+) {
+    match value {
+        ExportValue::String(target) => {
+            let condition = match conditions.is_empty() {
+                true => String::from("default"),
+                false => conditions.join("."),
+            };
+
+            targets.push((condition, target));
+        }
+        ExportValue::Null => {}
+        ExportValue::Array(values) => {
+            for value in values {
+                collect_condition_targets(value, conditions, targets);
+            }
+        }
+        ExportValue::HashMap(map) => {
+            for (condition, value) in map {
+                conditions.push(condition);
+                collect_condition_targets(value, conditions, targets);
+                conditions.pop();
+            }
+        }
+    }
+}
+
+/// Every target string in an export value, in no particular order.
+pub(crate) fn leaf_targets(value: &ExportValue) -> Vec<&str> {
+    match value {
+        ExportValue::String(target) => vec![target.as_str()],
+        ExportValue::Null => vec![],
+        ExportValue::Array(values) => values.iter().flat_map(leaf_targets).collect(),
+        ExportValue::HashMap(map) => map.values().flat_map(leaf_targets).collect(),
+    }
+}
+
+enum MapKind {
+    Subpaths,
+    Conditions,
+}
+
+/// Tell subpath maps from condition maps; mixing both kinds of keys is invalid.
+fn map_kind(map: &IndexMap<String, ExportValue>) -> Option<MapKind> {
+    let subpaths = map.keys().filter(|key| key.starts_with('.')).count();
+
+    if subpaths == 0 {
+        Some(MapKind::Conditions)
+    } else if subpaths == map.len() {
+        Some(MapKind::Subpaths)
+    } else {
+        None
+    }
+}
+
+/// An `imports` target is a `./` path or a bare package specifier.
+fn is_import_target(target: &str) -> bool {
+    is_relative_target(target)
+        || !(target.starts_with('.') || target.starts_with('/') || target.starts_with('#'))
+}
+
+fn is_relative_target(target: &str) -> bool {
+    target.starts_with("./")
+}
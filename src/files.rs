@@ -0,0 +1,176 @@
+use std::{
+    collections::HashSet,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{exports, Browser, PackageJSON};
+
+impl PackageJSON {
+    /// Get the entry targets that the `files` field leaves out of the published package
+    ///
+    /// `module`, `types`, the string form of `browser` and every `exports` target are checked against the
+    /// files the `files` patterns select under `root`. `main` and `bin` are not checked
+    /// since npm always packs them. A pattern target such as `./dist/*.js` is covered when
+    /// the `files` patterns select at least one file it matches. Without a `files` field
+    /// everything is published.
+    pub fn entry_covered_by_files(&self, root: &Path) -> Vec<String> {
+        let Some(included) = self.included_files(root) else {
+            return vec![];
+        };
+        let browser = self.browser.as_ref().and_then(Browser::entry);
+        let mut targets: Vec<&str> = [self.module.as_deref(), self.types.as_deref(), browser]
+            .into_iter()
+            .flatten()
+            .collect();
+
+        if let Some(exports) = self.exports.as_ref() {
+            let mut export_targets = exports::leaf_targets(exports);
+
+            export_targets.sort();
+            targets.extend(export_targets);
+        }
+
+        let mut uncovered: Vec<String> = vec![];
+
+        for target in targets {
+            let path = normalize_relative(target);
+            let covered = match target_pattern(&path) {
+                Some(pattern) => included.iter().any(|file| pattern.matches_path(file)),
+                None => included.contains(&path),
+            };
+
+            if !covered && !uncovered.iter().any(|t| t == target) {
+                uncovered.push(target.to_string());
+            }
+        }
+
+        uncovered
+    }
+
+    /// The files under `root` selected by the `files` field, relative to `root`, or `None`
+    /// when there is no `files` field
+    ///
+    /// Like npm, patterns without a `/` match at any depth, matched directories include
+    /// everything below them and `!` patterns exclude. `package.json`, the README and the
+    /// license file are always included.
+    pub(crate) fn included_files(&self, root: &Path) -> Option<HashSet<PathBuf>> {
+        let mut included = HashSet::new();
+        let mut excluded = HashSet::new();
+
+        for pattern in self.files.as_ref()? {
+            let (pattern, negated) = match pattern.strip_prefix('!') {
+                Some(pattern) => (pattern, true),
+                None => (pattern.as_str(), false),
+            };
+            let pattern = pattern.trim_start_matches("./").trim_end_matches('/');
+            let mut globs = vec![root.join(pattern)];
+
+            if !pattern.contains('/') {
+                globs.push(root.join("**").join(pattern));
+            }
+
+            for path in globs
+                .iter()
+                .filter_map(|glob| glob::glob(&glob.to_string_lossy()).ok())
+                .flat_map(|paths| paths.flatten())
+            {
+                let files = if path.is_dir() {
+                    walk_files(&path)
+                } else {
+                    vec![path]
+                };
+                let files = files
+                    .into_iter()
+                    .filter_map(|file| Some(file.strip_prefix(root).ok()?.to_path_buf()));
+
+                if negated {
+                    excluded.extend(files);
+                } else {
+                    included.extend(files);
+                }
+            }
+        }
+
+        let mut included: HashSet<PathBuf> = included.difference(&excluded).cloned().collect();
+
+        included.insert(PathBuf::from("package.json"));
+        included.extend(
+            fs::read_dir(root)
+                .into_iter()
+                .flatten()
+                .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+                .filter(|name| is_always_included(name))
+                .map(PathBuf::from),
+        );
+
+        Some(included)
+    }
+}
+
+/// Every file below `dir`, following symlinks.
+pub(crate) fn walk_files(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return vec![];
+    };
+
+    entries
+        .filter_map(|entry| entry.ok())
+        .flat_map(|entry| {
+            let path = entry.path();
+
+            if path.is_dir() {
+                walk_files(&path)
+            } else {
+                vec![path]
+            }
+        })
+        .collect()
+}
+
+/// The size in bytes of every file below the package directory `dir`, leaving out its
+/// nested `node_modules`.
+pub(crate) fn package_size(dir: &Path) -> u64 {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return 0;
+    };
+
+    entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_name() != "node_modules")
+        .map(|entry| {
+            let path = entry.path();
+
+            if path.is_dir() {
+                package_size(&path)
+            } else {
+                fs::metadata(&path).map_or(0, |metadata| metadata.len())
+            }
+        })
+        .sum()
+}
+
+fn is_always_included(file_name: &str) -> bool {
+    let file_name = file_name.to_ascii_uppercase();
+
+    ["README", "LICENSE", "LICENCE"]
+        .iter()
+        .any(|prefix| file_name.starts_with(prefix))
+}
+
+/// A glob for an `exports` pattern target, where `*` matches any substring, `/` included.
+fn target_pattern(target: &Path) -> Option<glob::Pattern> {
+    let target = target.to_str()?;
+
+    if !target.contains('*') {
+        return None;
+    }
+
+    let pattern: Vec<String> = target.split('*').map(glob::Pattern::escape).collect();
+
+    glob::Pattern::new(&pattern.join("*")).ok()
+}
+
+fn normalize_relative(target: &str) -> PathBuf {
+    Path::new(target.trim_start_matches("./")).to_path_buf()
+}
@@ -0,0 +1,245 @@
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    fs,
+    path::PathBuf,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    get_package_info_for, lookup_package_json, range::satisfies, try_load_package_info_with,
+    Options, PackageInfo, Required,
+};
+
+/// Resolve `name` as a dependency of `from`, looking in `from`'s own `node_modules` first
+/// and then in each ancestor's, like `require` does.
+///
+/// A dependency is present when its manifest is, even without a runtime entry, e.g. a
+/// types-only package.
+pub(crate) fn resolve_dependency(
+    from: &PackageInfo,
+    name: &str,
+    options: &Options,
+) -> Option<PackageInfo> {
+    let package_json_path = lookup_package_json(&from.root_path, name, options)?;
+
+    try_load_package_info_with(name, package_json_path, options, Required::Version).ok()
+}
+
+/// What an optional dependency resolved to, see [`resolve_optional`]
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub enum OptionalResolution {
+    /// The optional dependency is installed.
+    Present(Box<PackageInfo>),
+    /// The optional dependency is not installed, which is allowed.
+    AbsentButOptional,
+    /// The package does not declare `name` in `optionalDependencies`.
+    NotDeclared,
+}
+
+/// Resolve `name` from the `optionalDependencies` of `info`, like `require` would
+///
+/// An optional dependency that is not installed, e.g. a binary for another platform, is
+/// [`OptionalResolution::AbsentButOptional`] rather than an error.
+pub fn resolve_optional(info: &PackageInfo, name: &str, options: &Options) -> OptionalResolution {
+    let declared = info
+        .package_json
+        .optional_dependencies
+        .as_ref()
+        .is_some_and(|dependencies| dependencies.contains_key(name));
+
+    if !declared {
+        return OptionalResolution::NotDeclared;
+    }
+
+    match resolve_dependency(info, name, options) {
+        Some(pkg_info) => OptionalResolution::Present(Box::new(pkg_info)),
+        None => OptionalResolution::AbsentButOptional,
+    }
+}
+
+/// Where a dependency was installed relative to the package requiring it
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub enum DependencyLocation {
+    /// In the parent's own `node_modules`, at this directory.
+    Nested(PathBuf),
+    /// In the `node_modules` of one of the parent's ancestors, at this directory.
+    Hoisted(PathBuf),
+}
+
+/// Tell whether `dep` is installed nested under `parent` or hoisted above it
+///
+/// Returns `None` when `dep` cannot be resolved from `parent`.
+pub fn dependency_location(
+    parent: &PackageInfo,
+    dep: &str,
+    options: &Options,
+) -> Option<DependencyLocation> {
+    let package_json_path = lookup_package_json(&parent.root_path, dep, options)?;
+    let dir = package_json_path.parent()?.to_path_buf();
+
+    if dir.starts_with(parent.root_path.join(options.modules_dir_name())) {
+        Some(DependencyLocation::Nested(dir))
+    } else {
+        Some(DependencyLocation::Hoisted(dir))
+    }
+}
+
+/// Whether `name` resolves from `from`'s location, typically because it was hoisted, while
+/// `from` declares no dependency of any kind on it
+///
+/// Candidate names come from the caller, e.g. from an import scan, so phantom dependencies
+/// can be flagged.
+pub fn is_resolvable_but_undeclared(from: &PackageInfo, name: &str, options: &Options) -> bool {
+    !from.all_dependency_names().contains(name)
+        && lookup_package_json(&from.root_path, name, options).is_some()
+}
+
+/// The health of a package's installed dependencies, see [`verify_dependencies_installed`]
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+pub struct DependencyReport {
+    /// Dependencies installed in a version satisfying the declared range.
+    pub resolved: Vec<ResolvedDependency>,
+    /// Required dependencies that cannot be resolved.
+    pub missing: Vec<String>,
+    /// Dependencies installed in a version outside the declared range.
+    pub out_of_range: Vec<ResolvedDependency>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct ResolvedDependency {
+    pub name: String,
+    /// The declared range.
+    pub range: String,
+    /// The installed version.
+    pub version: String,
+    /// Where the dependency was resolved to.
+    pub root_path: PathBuf,
+}
+
+/// Resolve each runtime dependency of `info` from its location, and with
+/// [`Options::include_peers`] its peer dependencies, checking the installed versions
+///
+/// Missing optional dependencies and optional peers are not reported. Ranges that are not
+/// semver ranges, such as `workspace:` or git specs, count as satisfied. Each list is
+/// sorted by name.
+pub fn verify_dependencies_installed(info: &PackageInfo, options: &Options) -> DependencyReport {
+    let pkg_json = &info.package_json;
+    let mut declared: Vec<(&String, &String, bool)> = vec![];
+
+    for (dependencies, optional) in [
+        (&pkg_json.dependencies, false),
+        (&pkg_json.optional_dependencies, true),
+    ] {
+        for (name, range) in dependencies.iter().flatten() {
+            declared.push((name, range, optional));
+        }
+    }
+
+    if options.include_peers {
+        for (name, range) in pkg_json.peer_dependencies.iter().flatten() {
+            declared.push((name, range, info.is_optional_peer(name)));
+        }
+    }
+
+    declared.sort_by(|a, b| a.0.cmp(b.0));
+    declared.dedup_by(|a, b| a.0 == b.0);
+
+    let mut report = DependencyReport::default();
+
+    for (name, range, optional) in declared {
+        let Some(dependency) = resolve_dependency(info, name, options) else {
+            if !optional {
+                report.missing.push(name.clone());
+            }
+            continue;
+        };
+        let resolved = ResolvedDependency {
+            name: name.clone(),
+            range: range.clone(),
+            version: dependency.version,
+            root_path: dependency.root_path,
+        };
+
+        match satisfies(&resolved.version, range) {
+            Some(false) => report.out_of_range.push(resolved),
+            _ => report.resolved.push(resolved),
+        }
+    }
+
+    report
+}
+
+/// Runtime dependency names of a package: `dependencies` and `optionalDependencies`.
+pub(crate) fn runtime_dependency_names(pkg: &PackageInfo) -> Vec<&str> {
+    let pkg_json = &pkg.package_json;
+    let mut names: Vec<&str> = [&pkg_json.dependencies, &pkg_json.optional_dependencies]
+        .into_iter()
+        .flatten()
+        .flat_map(|dependencies| dependencies.keys().map(String::as_str))
+        .collect();
+
+    names.sort();
+    names.dedup();
+    names
+}
+
+/// Get the minimum depth of every package reachable through runtime dependencies from
+/// the installed package `name`, which has depth `0`
+///
+/// Dependencies are resolved from the location of the package requiring them, and each
+/// install is only visited once, so cycles terminate.
+pub fn dependency_depths(name: &str, options: &Options) -> HashMap<String, usize> {
+    match get_package_info_for(name, options) {
+        Some(root) => depths_from(root, options),
+        None => HashMap::new(),
+    }
+}
+
+/// Get the names of every package in the runtime dependency closure of `info`, resolved
+/// from where each dependent is installed
+///
+/// `info` itself is only included when a cycle leads back to it.
+pub fn reachable_dependencies(info: &PackageInfo, options: &Options) -> HashSet<String> {
+    runtime_dependency_names(info)
+        .into_iter()
+        .filter_map(|dependency| resolve_dependency(info, dependency, options))
+        .flat_map(|dependency| depths_from(dependency, options).into_keys())
+        .collect()
+}
+
+/// Get the declared runtime dependencies of `info` that are not part of its runtime
+/// graph because they cannot be resolved, sorted.
+pub fn unreachable_dependencies(info: &PackageInfo, options: &Options) -> Vec<String> {
+    let reachable = reachable_dependencies(info, options);
+
+    runtime_dependency_names(info)
+        .into_iter()
+        .filter(|dependency| !reachable.contains(*dependency))
+        .map(String::from)
+        .collect()
+}
+
+fn depths_from(root: PackageInfo, options: &Options) -> HashMap<String, usize> {
+    let mut depths = HashMap::new();
+    let mut visited = HashSet::new();
+    let mut queue = VecDeque::from([(root, 0)]);
+
+    while let Some((pkg, depth)) = queue.pop_front() {
+        let location = fs::canonicalize(&pkg.root_path).unwrap_or_else(|_| pkg.root_path.clone());
+
+        if !visited.insert(location) {
+            continue;
+        }
+
+        depths.entry(pkg.name.clone()).or_insert(depth);
+
+        for dependency in runtime_dependency_names(&pkg) {
+            if let Some(dependency) = resolve_dependency(&pkg, dependency, options) {
+                queue.push_back((dependency, depth + 1));
+            }
+        }
+    }
+
+    depths
+}
@@ -0,0 +1,106 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    installed::{scan_direct, scan_recursive},
+    node_modules_roots, Options, PackageInfo,
+};
+
+/// Every installed package, scanned once so that lookups do not touch the filesystem
+///
+/// The copies of a package are kept in the order [`crate::resolve_all_locations`] lists
+/// them, so [`PackageIndex::resolve`] returns the copy `get_package_info` would pick. The
+/// index is only valid for the [`Options`] it was built with and until packages are
+/// installed or removed; it can be cached between runs with [`PackageIndex::to_json`] and
+/// [`PackageIndex::from_json`].
+///
+/// # Exmaple
+/// ```
+/// use npm_pkg::{get_package_info, Options, PackageIndex};
+///
+/// let index = PackageIndex::build(&Options::default());
+/// let cached = PackageIndex::from_json(&index.to_json().unwrap()).unwrap();
+///
+/// assert_eq!(cached.resolve("consola"), get_package_info("consola", Options::default()).as_ref());
+/// ```
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+pub struct PackageIndex {
+    packages: HashMap<String, Vec<PackageInfo>>,
+}
+
+impl PackageIndex {
+    /// Scan the packages visible from `cwd`
+    ///
+    /// Packages directly in each `node_modules` up the tree are indexed, as well as the
+    /// copies nested in the `node_modules` of other packages below the first one. Packages
+    /// not allowed by `options` are left out.
+    pub fn build(options: &Options) -> Self {
+        let mut direct = vec![];
+        let mut nested = vec![];
+
+        for (depth, node_modules) in node_modules_roots(options).iter().enumerate() {
+            let packages = match depth {
+                0 => scan_recursive(node_modules, options),
+                _ => scan_direct(node_modules, options),
+            };
+
+            for pkg in packages
+                .into_iter()
+                .filter(|pkg| options.is_allowed(&pkg.name))
+            {
+                match pkg.root_path == node_modules.join(&pkg.name) {
+                    true => direct.push(pkg),
+                    false => nested.push(pkg),
+                }
+            }
+        }
+
+        nested.sort_by(|a, b| a.root_path.cmp(&b.root_path));
+
+        let mut packages: HashMap<String, Vec<PackageInfo>> = HashMap::new();
+
+        for pkg in direct.into_iter().chain(nested) {
+            packages.entry(pkg.name.clone()).or_default().push(pkg);
+        }
+
+        Self { packages }
+    }
+
+    /// Get the copy of package `name` that `get_package_info` would pick.
+    ///
+    /// Like `get_package_info`, this is `None` when that copy has no runtime entry, e.g.
+    /// `@types` packages, even though it is kept in [`PackageIndex::resolve_all`].
+    pub fn resolve(&self, name: &str) -> Option<&PackageInfo> {
+        self.resolve_all(name)
+            .first()
+            .filter(|pkg| !pkg.package_entry.as_os_str().is_empty())
+    }
+
+    /// Get every indexed copy of package `name`, in lookup order.
+    pub fn resolve_all(&self, name: &str) -> &[PackageInfo] {
+        self.packages
+            .get(name)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Serialize the index to JSON.
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(self)
+    }
+
+    /// Read an index serialized with [`PackageIndex::to_json`].
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    /// The number of package names indexed.
+    pub fn len(&self) -> usize {
+        self.packages.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.packages.is_empty()
+    }
+}
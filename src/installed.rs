@@ -0,0 +1,486 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::{Path, PathBuf},
+    sync::{LazyLock, Mutex},
+    time::{Duration, SystemTime},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    files::package_size, range::compare_versions, resolve, try_load_package_info_with, License,
+    Options, PackageError, PackageInfo, Required,
+};
+
+/// Difference between two installed `node_modules` trees.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+pub struct InstallDiff {
+    /// Packages present in the second tree only.
+    pub added: Vec<PackageInfo>,
+    /// Packages present in the first tree only.
+    pub removed: Vec<PackageInfo>,
+    /// Packages present in both trees with different versions.
+    pub changed: Vec<VersionChange>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct VersionChange {
+    pub name: String,
+    pub from: String,
+    pub to: String,
+}
+
+/// List every package installed directly under `<cwd>/node_modules`
+///
+/// Scoped packages (`@scope/name`) are included, dot entries such as `.bin`
+/// are skipped, and packages whose `package.json` cannot be read are ignored. Packages
+/// without a runtime entry, such as `@types/*` ones, are listed with an empty
+/// [`PackageInfo::package_entry`], although [`get_package_info`](crate::get_package_info)
+/// does not return them.
+///
+/// With [`Options::scan_cache`], the result is reused until a package is added to or
+/// removed from `node_modules`, as told by the modification times of `node_modules` and
+/// its scope directories. Edits inside a package directory are not detected, use
+/// [`invalidate_scan_cache`] then.
+///
+/// # Exmaple
+/// ```
+/// use npm_pkg::{list_installed_packages, Options};
+///
+/// let names: Vec<String> = list_installed_packages(&Options::default())
+///     .into_iter()
+///     .map(|pkg| pkg.name)
+///     .collect();
+///
+/// assert!(names.contains(&String::from("@jridgewell/sourcemap-codec")));
+/// ```
+pub fn list_installed_packages(options: &Options) -> Vec<PackageInfo> {
+    if !options.scan_cache {
+        return scan_with_errors(options).0;
+    }
+
+    let Some((key, fingerprint)) = scan_key(options) else {
+        return scan_with_errors(options).0;
+    };
+
+    if let Some(cached) = SCAN_CACHE.lock().unwrap().get(&key) {
+        if cached.fingerprint == fingerprint {
+            return cached.packages.clone();
+        }
+    }
+
+    let packages = scan_with_errors(options).0;
+
+    if fingerprint.iter().all(is_settled) {
+        SCAN_CACHE.lock().unwrap().insert(
+            key,
+            CachedScan {
+                fingerprint,
+                packages: packages.clone(),
+            },
+        );
+    }
+
+    packages
+}
+
+/// Forget every scan cached for [`Options::scan_cache`].
+pub fn invalidate_scan_cache() {
+    SCAN_CACHE.lock().unwrap().clear();
+}
+
+/// Everything in [`Options`] that changes the result of a scan.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct ScanKey {
+    node_modules: PathBuf,
+    follow_symlinks: bool,
+    posix_paths: bool,
+    retain_raw: bool,
+    allowed_packages: Option<Vec<String>>,
+    denied_packages: Option<Vec<String>>,
+    conditions: Vec<String>,
+}
+
+struct CachedScan {
+    /// Modification times of `node_modules` and of its scope directories.
+    fingerprint: Vec<SystemTime>,
+    packages: Vec<PackageInfo>,
+}
+
+static SCAN_CACHE: LazyLock<Mutex<HashMap<ScanKey, CachedScan>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Directories modified this recently may still change within the same mtime tick.
+const MTIME_GRANULARITY: Duration = Duration::from_secs(2);
+
+/// Get the cache key for a scan, and the mtimes it is valid for; `None` when an mtime
+/// cannot be read, so the scan is never cached.
+fn scan_key(options: &Options) -> Option<(ScanKey, Vec<SystemTime>)> {
+    let node_modules = fs::canonicalize(resolve(options.modules_dir_name(), options).ok()?).ok()?;
+    let mut fingerprint = vec![fs::metadata(&node_modules).ok()?.modified().ok()?];
+
+    for dir_name in visible_dir_names(&node_modules) {
+        if dir_name.starts_with('@') {
+            fingerprint.push(
+                fs::metadata(node_modules.join(dir_name))
+                    .ok()?
+                    .modified()
+                    .ok()?,
+            );
+        }
+    }
+
+    let sorted = |names: &Option<HashSet<String>>| {
+        names.as_ref().map(|names| {
+            let mut names: Vec<String> = names.iter().cloned().collect();
+
+            names.sort();
+            names
+        })
+    };
+    let key = ScanKey {
+        node_modules,
+        follow_symlinks: options.follow_symlinks,
+        posix_paths: options.posix_paths,
+        retain_raw: options.retain_raw,
+        allowed_packages: sorted(&options.allowed_packages),
+        denied_packages: sorted(&options.denied_packages),
+        conditions: options.conditions.clone(),
+    };
+
+    Some((key, fingerprint))
+}
+
+fn is_settled(mtime: &SystemTime) -> bool {
+    SystemTime::now()
+        .duration_since(*mtime)
+        .is_ok_and(|age| age >= MTIME_GRANULARITY)
+}
+
+/// Lazily iterate the packages [`list_installed_packages`] would return
+///
+/// Each `package.json` is only read when the iterator reaches it.
+pub fn iter_installed_packages<'a>(
+    options: &'a Options<'a>,
+) -> impl Iterator<Item = PackageInfo> + 'a {
+    let node_modules = resolve(options.modules_dir_name(), options).ok();
+    let names = node_modules
+        .as_deref()
+        .map(installed_package_names)
+        .unwrap_or_default();
+
+    names.into_iter().filter_map(move |name| {
+        let package_json_path = node_modules.as_ref()?.join(&name).join("package.json");
+
+        try_load_package_info_with(&name, package_json_path, options, Required::Version).ok()
+    })
+}
+
+/// Filters for [`find_installed`]; every filter that is set must match.
+#[derive(Clone, Debug, Default)]
+pub struct InstalledQuery {
+    /// Glob matched against the package name, e.g. `@babel/*`.
+    pub name: Option<String>,
+    /// A keyword the package must declare.
+    pub keyword: Option<String>,
+    /// Glob matched against the `license` field, e.g. `GPL-*`.
+    pub license: Option<String>,
+}
+
+impl InstalledQuery {
+    pub fn matches(&self, pkg: &PackageInfo) -> bool {
+        let pkg_json = &pkg.package_json;
+
+        self.name
+            .as_ref()
+            .is_none_or(|pattern| glob_matches(pattern, &pkg.name))
+            && self.keyword.as_ref().is_none_or(|keyword| {
+                pkg_json
+                    .keywords
+                    .as_ref()
+                    .is_some_and(|keywords| keywords.contains(keyword))
+            })
+            && self.license.as_ref().is_none_or(|pattern| {
+                pkg_json
+                    .license
+                    .as_ref()
+                    .and_then(License::id)
+                    .is_some_and(|license| glob_matches(pattern, license))
+            })
+    }
+}
+
+/// Find the installed packages matching `query`
+///
+/// # Exmaple
+/// ```
+/// use npm_pkg::{find_installed, InstalledQuery, Options};
+///
+/// let query = InstalledQuery {
+///     name: Some(String::from("@jridgewell/*")),
+///     ..Default::default()
+/// };
+/// let packages = find_installed(&query, &Options::default());
+///
+/// assert_eq!(packages.len(), 1);
+/// assert_eq!(packages[0].name, "@jridgewell/sourcemap-codec");
+/// ```
+pub fn find_installed(query: &InstalledQuery, options: &Options) -> Vec<PackageInfo> {
+    iter_installed_packages(options)
+        .filter(|pkg| query.matches(pkg))
+        .collect()
+}
+
+/// Scan `<cwd>/node_modules` like [`list_installed_packages`], collecting failures
+///
+/// Every package directory that could not be loaded is reported with the path of its
+/// `package.json` instead of being silently skipped.
+pub fn scan_with_errors(options: &Options) -> (Vec<PackageInfo>, Vec<(PathBuf, PackageError)>) {
+    let Ok(node_modules) = resolve(options.modules_dir_name(), options) else {
+        return (vec![], vec![]);
+    };
+    let mut packages = vec![];
+    let mut errors = vec![];
+
+    for name in installed_package_names(&node_modules) {
+        let package_json_path = node_modules.join(&name).join("package.json");
+
+        match try_load_package_info_with(
+            &name,
+            package_json_path.clone(),
+            options,
+            Required::Version,
+        ) {
+            Ok(pkg) => packages.push(pkg),
+            Err(error) => errors.push((package_json_path, error)),
+        }
+    }
+
+    packages.sort_by(|a, b| a.name.cmp(&b.name));
+    errors.sort_by(|a, b| a.0.cmp(&b.0));
+    (packages, errors)
+}
+
+/// Map every package name to the versions installed anywhere in the `<cwd>/node_modules`
+/// tree, nested copies included
+///
+/// Versions are deduplicated and sorted by semver precedence, so a name with more than one
+/// version is installed in several incompatible copies.
+pub fn installed_versions(options: &Options) -> HashMap<String, Vec<String>> {
+    let mut versions: HashMap<String, Vec<String>> = HashMap::new();
+    let Ok(node_modules) = resolve(options.modules_dir_name(), options) else {
+        return versions;
+    };
+
+    for pkg in scan_recursive(&node_modules, options) {
+        versions.entry(pkg.name).or_default().push(pkg.version);
+    }
+
+    for list in versions.values_mut() {
+        list.sort_by(|a, b| compare_versions(a, b));
+        list.dedup();
+    }
+
+    versions
+}
+
+/// A package installed in copies of more than one version, see [`duplicate_version_report`]
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct DuplicateReport {
+    pub name: String,
+    /// The distinct versions installed, sorted by semver precedence.
+    pub versions: Vec<String>,
+    /// Every copy, sorted by version then path.
+    pub copies: Vec<InstalledCopy>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct InstalledCopy {
+    pub version: String,
+    pub root_path: PathBuf,
+    /// The size of the package directory, without its nested `node_modules`.
+    pub size: u64,
+}
+
+impl DuplicateReport {
+    /// The bytes that would be saved by keeping only the largest copy.
+    pub fn wasted_bytes(&self) -> u64 {
+        let sizes = self.copies.iter().map(|copy| copy.size);
+
+        sizes.clone().sum::<u64>() - sizes.max().unwrap_or(0)
+    }
+}
+
+/// Report every package installed in more than one version anywhere in the
+/// `<cwd>/node_modules` tree, with the on-disk size of each copy
+///
+/// The report is sorted by [`DuplicateReport::wasted_bytes`], largest first, then by name.
+pub fn duplicate_version_report(options: &Options) -> Vec<DuplicateReport> {
+    let Ok(node_modules) = resolve(options.modules_dir_name(), options) else {
+        return vec![];
+    };
+    let mut copies: HashMap<String, Vec<InstalledCopy>> = HashMap::new();
+
+    for pkg in scan_recursive(&node_modules, options) {
+        copies.entry(pkg.name).or_default().push(InstalledCopy {
+            version: pkg.version,
+            size: package_size(&pkg.root_path),
+            root_path: pkg.root_path,
+        });
+    }
+
+    let mut report: Vec<DuplicateReport> = copies
+        .into_iter()
+        .filter_map(|(name, mut copies)| {
+            copies.sort_by(|a, b| {
+                compare_versions(&a.version, &b.version).then_with(|| a.root_path.cmp(&b.root_path))
+            });
+
+            let mut versions: Vec<String> =
+                copies.iter().map(|copy| copy.version.clone()).collect();
+
+            versions.dedup();
+
+            (versions.len() > 1).then_some(DuplicateReport {
+                name,
+                versions,
+                copies,
+            })
+        })
+        .collect();
+
+    report.sort_by(|a, b| {
+        b.wasted_bytes()
+            .cmp(&a.wasted_bytes())
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    report
+}
+
+/// Load every package directly in `node_modules`.
+pub(crate) fn scan_direct(node_modules: &Path, options: &Options) -> Vec<PackageInfo> {
+    installed_package_names(node_modules)
+        .into_iter()
+        .filter_map(|name| {
+            let package_json_path = node_modules.join(&name).join("package.json");
+
+            try_load_package_info_with(&name, package_json_path, options, Required::Version).ok()
+        })
+        .collect()
+}
+
+/// Load every package in `node_modules` and, recursively, in their own `node_modules`
+///
+/// Each `node_modules` is visited once by its real path, so symlinked packages that link
+/// back to each other, as in workspaces, do not loop.
+pub(crate) fn scan_recursive(node_modules: &Path, options: &Options) -> Vec<PackageInfo> {
+    let mut packages = vec![];
+
+    scan_tree(node_modules, options, &mut HashSet::new(), &mut packages);
+    packages
+}
+
+fn scan_tree(
+    node_modules: &Path,
+    options: &Options,
+    visited: &mut HashSet<PathBuf>,
+    packages: &mut Vec<PackageInfo>,
+) {
+    let Ok(real_path) = fs::canonicalize(node_modules) else {
+        return;
+    };
+
+    if !visited.insert(real_path) {
+        return;
+    }
+
+    for name in installed_package_names(node_modules) {
+        let dir = node_modules.join(&name);
+
+        if let Ok(pkg) =
+            try_load_package_info_with(&name, dir.join("package.json"), options, Required::Version)
+        {
+            packages.push(pkg);
+        }
+
+        scan_tree(
+            &dir.join(options.modules_dir_name()),
+            options,
+            visited,
+            packages,
+        );
+    }
+}
+
+/// Compare the packages installed for two option sets (usually two `cwd`s)
+///
+/// Packages are joined on name; the result lists are sorted by name.
+pub fn diff_installed(a: &Options, b: &Options) -> InstallDiff {
+    let before = by_name(list_installed_packages(a));
+    let after = by_name(list_installed_packages(b));
+    let mut diff = InstallDiff::default();
+
+    for (name, pkg) in &after {
+        match before.get(name) {
+            None => diff.added.push(pkg.clone()),
+            Some(old) if old.version != pkg.version => diff.changed.push(VersionChange {
+                name: name.clone(),
+                from: old.version.clone(),
+                to: pkg.version.clone(),
+            }),
+            Some(_) => {}
+        }
+    }
+
+    for (name, pkg) in before {
+        if !after.contains_key(&name) {
+            diff.removed.push(pkg);
+        }
+    }
+
+    diff.added.sort_by(|a, b| a.name.cmp(&b.name));
+    diff.removed.sort_by(|a, b| a.name.cmp(&b.name));
+    diff.changed.sort_by(|a, b| a.name.cmp(&b.name));
+    diff
+}
+
+fn glob_matches(pattern: &str, value: &str) -> bool {
+    glob::Pattern::new(pattern).is_ok_and(|pattern| pattern.matches(value))
+}
+
+fn by_name(packages: Vec<PackageInfo>) -> HashMap<String, PackageInfo> {
+    packages
+        .into_iter()
+        .map(|pkg| (pkg.name.clone(), pkg))
+        .collect()
+}
+
+fn installed_package_names(node_modules: &Path) -> Vec<String> {
+    let mut names = vec![];
+
+    for dir_name in visible_dir_names(node_modules) {
+        if dir_name.starts_with('@') {
+            for scoped in visible_dir_names(&node_modules.join(&dir_name)) {
+                names.push(format!("{}/{}", dir_name, scoped));
+            }
+        } else {
+            names.push(dir_name);
+        }
+    }
+
+    names
+}
+
+fn visible_dir_names(dir: &Path) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return vec![];
+    };
+
+    entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter(|name| !name.starts_with('.'))
+        .collect()
+}
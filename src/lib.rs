@@ -1,62 +1,493 @@
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     env::current_dir,
-    fs,
-    path::{Path, PathBuf},
+    fs, io,
+    path::{Component, Path, PathBuf},
     sync::LazyLock,
 };
 
+use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
 use validate_npm_package_name::validate;
 
-#[derive(Default)]
+#[cfg(feature = "tokio")]
+mod r#async;
+mod cache;
+mod error;
+mod explain;
+mod exports;
+mod files;
+mod graph;
+mod index;
+mod installed;
+mod overrides;
+mod partial;
+mod pnpm;
+mod range;
+mod registry;
+mod validate;
+mod workspace;
+
+pub use cache::PackageCache;
+pub use error::{CycleError, PackageError};
+pub use explain::{explain_resolution, ResolutionExplanation};
+pub use exports::{
+    get_package_export, get_package_subpath, required_conditions_for, resolve_import,
+    try_get_package_export,
+};
+pub use graph::{
+    dependency_depths, dependency_location, is_resolvable_but_undeclared, reachable_dependencies,
+    resolve_optional, unreachable_dependencies, verify_dependencies_installed, DependencyLocation,
+    DependencyReport, OptionalResolution, ResolvedDependency,
+};
+pub use index::PackageIndex;
+pub use installed::{
+    diff_installed, duplicate_version_report, find_installed, installed_versions,
+    invalidate_scan_cache, iter_installed_packages, list_installed_packages, scan_with_errors,
+    DuplicateReport, InstallDiff, InstalledCopy, InstalledQuery, VersionChange,
+};
+pub use overrides::OverrideValue;
+pub use partial::{get_partial_package_info, PartialPackageInfo};
+pub use pnpm::resolve_via_pnpm_store;
+#[cfg(feature = "tokio")]
+pub use r#async::get_package_info_async;
+pub use range::{engine_satisfies, package_satisfies};
+pub use registry::{registry_for_scope, AbbreviatedMetadata, Dist};
+pub use validate::ValidationWarning;
+pub use workspace::{
+    check_workspace_versions, get_workspace_packages, owning_workspace_package,
+    workspace_build_order, workspace_graph, WorkspaceMismatch, Workspaces,
+};
+
 pub struct Options<'a> {
     pub cwd: Option<&'a str>,
+    /// Canonicalize package directories into [`PackageInfo::real_path`].
+    pub follow_symlinks: bool,
+    /// Only these package names may be resolved.
+    pub allowed_packages: Option<HashSet<String>>,
+    /// These package names are never resolved, even when also allowed.
+    pub denied_packages: Option<HashSet<String>>,
+    /// The `exports` conditions to match; the first key of a conditions object that is
+    /// among them, or is `default`, wins.
+    pub conditions: Vec<String>,
+    /// Use `/` as the separator in the paths of returned [`PackageInfo`]s on every platform.
+    pub posix_paths: bool,
+    /// Reuse the result of [`list_installed_packages`] while `node_modules` is unchanged.
+    pub scan_cache: bool,
+    /// Keep the `package.json` text in [`PackageInfo::raw_json`].
+    pub retain_raw: bool,
+    /// Also check `peerDependencies` in [`verify_dependencies_installed`].
+    pub include_peers: bool,
+    /// The directory `node_modules` and the root `package.json` are resolved from.
+    pub anchor: Anchor,
+    /// Look packages up as seen from this file or directory instead of `cwd`, e.g. the
+    /// source file importing them.
+    pub from: Option<&'a Path>,
+    /// The name of the directories packages are installed in, `node_modules` by default.
+    pub modules_dir: Option<&'a str>,
+}
+
+/// Where resolution starts, see [`Options::anchor`]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum Anchor {
+    /// [`Options::cwd`], or the current directory.
+    #[default]
+    Cwd,
+    /// The nearest ancestor of [`Options::cwd`] containing `.git`, or `Cwd` when there is
+    /// none.
+    GitRoot,
+    /// This directory, regardless of [`Options::cwd`].
+    Custom(PathBuf),
+}
+
+impl Default for Options<'_> {
+    fn default() -> Self {
+        Options {
+            cwd: None,
+            follow_symlinks: false,
+            allowed_packages: None,
+            denied_packages: None,
+            conditions: ["node", "import", "require", "default"]
+                .map(String::from)
+                .to_vec(),
+            posix_paths: false,
+            scan_cache: false,
+            retain_raw: false,
+            include_peers: false,
+            anchor: Anchor::Cwd,
+            from: None,
+            modules_dir: None,
+        }
+    }
 }
 
+impl Options<'_> {
+    pub(crate) fn modules_dir_name(&self) -> &str {
+        self.modules_dir.unwrap_or("node_modules")
+    }
+
+    pub(crate) fn condition_list(&self) -> Vec<&str> {
+        self.conditions.iter().map(String::as_str).collect()
+    }
+
+    /// Whether `name` passes the allowlist and denylist; the denylist wins.
+    pub fn is_allowed(&self, name: &str) -> bool {
+        !self
+            .denied_packages
+            .as_ref()
+            .is_some_and(|denied| denied.contains(name))
+            && self
+                .allowed_packages
+                .as_ref()
+                .is_none_or(|allowed| allowed.contains(name))
+    }
+}
+
+/// The `bin` field
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(untagged)]
 pub enum BinType {
+    /// A single executable, named after the package.
     String(String),
+    /// Command names mapped to executables.
     HashMap(HashMap<String, String>),
+    /// Executables each named after their file without its extension
+    ///
+    /// npm only accepts a string or an object; the array form is tolerated for manifests
+    /// written by tooling that gets it wrong.
+    Array(Vec<String>),
+}
+
+/// The `browser` field
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(untagged)]
+pub enum Browser {
+    /// The entry for browsers, replacing `main`.
+    String(String),
+    /// Module paths or package names mapped to their browser replacement.
+    Map(HashMap<String, BrowserValue>),
+}
+
+/// A replacement in the map form of `browser`
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(untagged)]
+pub enum BrowserValue {
+    Path(String),
+    /// `false` replaces the module with an empty one.
+    Disabled(bool),
+}
+
+impl Browser {
+    /// The entry for browsers, only given by the string form.
+    pub fn entry(&self) -> Option<&str> {
+        match self {
+            Browser::String(entry) => Some(entry),
+            Browser::Map(_) => None,
+        }
+    }
+}
+
+/// The `repository` field
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(untagged)]
+pub enum Repository {
+    /// A URL, or a shorthand such as `user/repo` or `github:user/repo`.
+    String(String),
+    Object {
+        #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+        r#type: Option<String>,
+        url: String,
+        /// The directory of the package in a monorepo.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        directory: Option<String>,
+    },
+}
+
+/// The `bugs` field
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(untagged)]
+pub enum Bugs {
+    /// The issue tracker URL.
+    String(String),
+    Object {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        url: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        email: Option<String>,
+    },
 }
 
+/// The `license` field
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(untagged)]
+pub enum License {
+    /// An SPDX expression, `UNLICENSED` or `SEE LICENSE IN <file>`.
+    String(String),
+    /// The deprecated `{ "type": ..., "url": ... }` form.
+    Object {
+        #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+        r#type: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        url: Option<String>,
+    },
+}
+
+impl License {
+    /// The license as written, or the `type` of the object form.
+    pub fn id(&self) -> Option<&str> {
+        match self {
+            License::String(id) => Some(id),
+            License::Object { r#type, .. } => r#type.as_deref(),
+        }
+    }
+}
+
+/// A person, as in `author` or `contributors`
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(untagged)]
+pub enum Person {
+    /// `"Name <email> (url)"`, with the email and url optional.
+    String(String),
+    Object {
+        #[serde(default)]
+        name: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        email: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        url: Option<String>,
+    },
+}
+
+/// The `funding` field
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(untagged)]
+pub enum Funding {
+    /// A URL.
+    String(String),
+    Object {
+        #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+        r#type: Option<String>,
+        url: String,
+    },
+    /// Several sources, in order of preference.
+    Array(Vec<Funding>),
+}
+
+/// A value of the `exports` field, at any level of nesting
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(untagged)]
 pub enum ExportValue {
+    /// A target path, e.g. `"./dist/index.js"`.
     String(String),
-    HashMap(HashMap<String, String>),
+    /// Fallback targets, tried in order.
+    Array(Vec<ExportValue>),
+    /// Subpaths (keys starting with `.`) or conditions, in the order they are written, as
+    /// the first matching condition wins.
+    HashMap(IndexMap<String, ExportValue>),
+    /// `null`, which blocks the subpath.
+    Null,
+}
+
+/// The module format declared by the `type` field
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ModuleType {
+    /// `"type": "module"`
+    Module,
+    /// `"type": "commonjs"`
+    CommonJs,
+    /// No (or an unrecognized) `type`, which Node treats as CommonJS.
+    Unspecified,
+}
+
+/// Whether an entry is loaded as an ES module or as CommonJS
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+pub enum ModuleKind {
+    Esm,
+    #[default]
+    Cjs,
+}
+
+/// The environment an entry is resolved for
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Target {
+    Node,
+    Browser,
+    /// Deno's `npm:` specifiers, which ignore the `browser` field.
+    Deno,
+    /// React Native's Metro bundler, which prefers the `react-native` field.
+    ReactNative,
+}
+
+impl Target {
+    /// The `exports` conditions matched for this target.
+    pub fn conditions(&self) -> &'static [&'static str] {
+        match self {
+            Target::Node => &["node", "import", "require", "default"],
+            Target::Browser => &["browser", "import", "require", "default"],
+            Target::Deno => &["deno", "node", "import", "default"],
+            Target::ReactNative => &["react-native", "browser", "import", "require", "default"],
+        }
+    }
 }
 
+const TYPES_CONDITIONS: &[&str] = &["types", "node", "import", "require", "default"];
+
 #[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
 pub struct PackageJSON {
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub name: Option<String>,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_relaxed_version",
+        skip_serializing_if = "Option::is_none"
+    )]
     pub version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub homepage: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub repository: Option<Repository>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bugs: Option<Bugs>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub keywords: Option<Vec<String>>,
-    pub license: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub license: Option<License>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub private: Option<bool>,
-    pub author: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub author: Option<Person>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contributors: Option<Vec<Person>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub funding: Option<Funding>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub files: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub r#type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub main: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub module: Option<String>,
-    #[serde(flatten)]
-    pub exports: Option<HashMap<String, ExportValue>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exports: Option<ExportValue>,
+    /// Package-internal `#` specifiers, see [`resolve_import`].
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub imports: Option<IndexMap<String, ExportValue>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub types: Option<String>,
-    pub browser: Option<String>,
-    #[serde(flatten)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub browser: Option<Browser>,
+    #[serde(rename = "react-native", skip_serializing_if = "Option::is_none")]
+    pub react_native: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub bin: Option<BinType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub scripts: Option<HashMap<String, String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub dependencies: Option<HashMap<String, String>>,
-    #[serde(rename = "devDependencies")]
+    #[serde(rename = "devDependencies", skip_serializing_if = "Option::is_none")]
     pub dev_dependencies: Option<HashMap<String, String>>,
-    #[serde(rename = "peerDependencies")]
+    #[serde(rename = "peerDependencies", skip_serializing_if = "Option::is_none")]
     pub peer_dependencies: Option<HashMap<String, String>>,
-    #[serde(rename = "peerDependenciesMeta")]
-    pub peer_dependencies_meta: Option<HashMap<String, String>>,
-    #[serde(rename = "optionalDependencies")]
+    #[serde(
+        rename = "peerDependenciesMeta",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub peer_dependencies_meta: Option<HashMap<String, PeerDependencyMeta>>,
+    #[serde(
+        rename = "optionalDependencies",
+        skip_serializing_if = "Option::is_none"
+    )]
     pub optional_dependencies: Option<HashMap<String, String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub engines: Option<HashMap<String, String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub directories: Option<HashMap<String, String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dist: Option<Dist>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub workspaces: Option<Workspaces>,
+    #[serde(rename = "publishConfig", skip_serializing_if = "Option::is_none")]
+    pub publish_config: Option<HashMap<String, serde_json::Value>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub overrides: Option<HashMap<String, OverrideValue>>,
+    /// Every field not modeled above, kept as is.
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+/// An entry of `peerDependenciesMeta`
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+pub struct PeerDependencyMeta {
+    /// The peer may be left uninstalled without a warning.
+    pub optional: Option<bool>,
+}
+
+/// Accept a `version` written as a number or boolean, as some hand-edited manifests do,
+/// coercing it to its JSON text.
+fn deserialize_relaxed_version<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    match serde_json::Value::deserialize(deserializer)? {
+        serde_json::Value::Null => Ok(None),
+        serde_json::Value::String(version) => Ok(Some(version)),
+        value @ (serde_json::Value::Number(_) | serde_json::Value::Bool(_)) => {
+            Ok(Some(value.to_string()))
+        }
+        value => Err(serde::de::Error::custom(format!(
+            "expected a version string, found {}",
+            value
+        ))),
+    }
+}
+
+/// Manifest fields that `publishConfig` may override at publish time.
+const PUBLISH_OVERRIDES: &[&str] = &[
+    "bin", "browser", "exports", "main", "module", "type", "types",
+];
+
+impl PackageJSON {
+    /// Get the manifest as it would be published
+    ///
+    /// Entry-point fields set in `publishConfig` (`main`, `module`, `types`, `bin`,
+    /// `exports`, `browser`, `type`) replace the top-level ones and are removed from
+    /// `publishConfig`; registry settings such as `access` or `tag` are kept. npm ships
+    /// `devDependencies` and `scripts` in the tarball, so they are left as they are.
+    pub fn to_published(&self) -> PackageJSON {
+        let Some(publish_config) = self.publish_config.as_ref() else {
+            return self.clone();
+        };
+        let Ok(serde_json::Value::Object(mut manifest)) = serde_json::to_value(self) else {
+            return self.clone();
+        };
+        let mut remaining = HashMap::new();
+
+        for (key, value) in publish_config {
+            if PUBLISH_OVERRIDES.contains(&key.as_str()) {
+                manifest.insert(key.clone(), value.clone());
+            } else {
+                remaining.insert(key.clone(), value.clone());
+            }
+        }
+
+        let published = serde_json::from_value(serde_json::Value::Object(manifest));
+
+        published.map_or_else(
+            |_| self.clone(),
+            |published: PackageJSON| PackageJSON {
+                publish_config: (!remaining.is_empty()).then_some(remaining),
+                ..published
+            },
+        )
+    }
+
+    /// Whether the package declares `"type": "module"`.
+    pub(crate) fn is_module(&self) -> bool {
+        self.r#type.as_ref().is_some_and(|t| t == "module")
+    }
 }
 
 #[derive(Default, Clone, Debug, PartialEq, Serialize, Deserialize)]
@@ -64,9 +495,490 @@ pub struct PackageInfo {
     pub name: String,
     pub version: String,
     pub root_path: PathBuf,
+    /// `root_path` with symlinks resolved when [`Options::follow_symlinks`] is set,
+    /// otherwise equal to `root_path`. Under pnpm, this is the package directory inside
+    /// `node_modules/.pnpm`.
+    pub real_path: PathBuf,
     pub package_json_path: PathBuf,
+    /// Empty when the package has no runtime entry and was loaded without one, e.g. a
+    /// types-only package listed by [`list_installed_packages`].
     pub package_entry: PathBuf,
+    /// How `package_entry` is loaded: `.mjs` and `.cjs` entries by their extension, other
+    /// entries by the package `type`.
+    pub module_kind: ModuleKind,
     pub package_json: PackageJSON,
+    /// The `package.json` text as read, when [`Options::retain_raw`] is set.
+    pub raw_json: Option<String>,
+}
+
+impl PackageInfo {
+    /// Resolve a file relative to the package root
+    ///
+    /// Returns the canonicalized path, or `None` when the file does not exist or the path
+    /// escapes `root_path` (e.g. through `..` or a symlink).
+    pub fn resolve_asset(&self, relative: &str) -> Option<PathBuf> {
+        let root = fs::canonicalize(&self.root_path).ok()?;
+        let asset = fs::canonicalize(root.join(relative)).ok()?;
+
+        asset.starts_with(&root).then_some(asset)
+    }
+
+    /// Get the scope of a scoped package, e.g. `@babel` for `@babel/core`.
+    pub fn scope(&self) -> Option<&str> {
+        split_scope(&self.name).0
+    }
+
+    /// Get the name without its scope, e.g. `core` for `@babel/core`.
+    pub fn unscoped_name(&self) -> &str {
+        split_scope(&self.name).1
+    }
+
+    /// Get the DefinitelyTyped package providing types for this package, e.g.
+    /// `@types/express` for `express` and `@types/babel__core` for `@babel/core`
+    ///
+    /// An `@types` package is its own types package.
+    pub fn types_package_name(&self) -> String {
+        match self.scope() {
+            Some("@types") => self.name.clone(),
+            Some(scope) => format!("@types/{}__{}", &scope[1..], self.unscoped_name()),
+            None => format!("@types/{}", self.name),
+        }
+    }
+
+    /// Get the module format declared by the `type` field.
+    pub fn module_type(&self) -> ModuleType {
+        match self.package_json.r#type.as_deref() {
+            Some("module") => ModuleType::Module,
+            Some("commonjs") => ModuleType::CommonJs,
+            _ => ModuleType::Unspecified,
+        }
+    }
+
+    /// Whether `.js` files of the package are ES modules.
+    pub fn is_esm(&self) -> bool {
+        self.module_type() == ModuleType::Module
+    }
+
+    /// Whether `.js` files of the package are CommonJS, explicitly or by default.
+    pub fn is_cjs(&self) -> bool {
+        !self.is_esm()
+    }
+
+    /// Get the executable installed for `command`, see [`PackageInfo::bin_paths`]
+    ///
+    /// The string form of `bin` only provides a command named after the package (without
+    /// its scope).
+    pub fn bin_path(&self, command: &str) -> Option<PathBuf> {
+        self.bin_paths().remove(command)
+    }
+
+    /// Get every command the package installs, mapped to its executable
+    ///
+    /// Without a `bin` field, each file below `directories.bin` becomes a command named
+    /// after the file without its extension, like each file of an array `bin`.
+    pub fn bin_paths(&self) -> HashMap<String, PathBuf> {
+        let pkg_json = &self.package_json;
+
+        match pkg_json.bin.as_ref() {
+            Some(BinType::String(bin)) => HashMap::from([(
+                self.unscoped_name().to_string(),
+                join_manifest_path(&self.root_path, bin),
+            )]),
+            Some(BinType::HashMap(bins)) => bins
+                .iter()
+                .map(|(command, bin)| (command.clone(), join_manifest_path(&self.root_path, bin)))
+                .collect(),
+            Some(BinType::Array(bins)) => bins
+                .iter()
+                .filter_map(|bin| {
+                    let path = join_manifest_path(&self.root_path, bin);
+
+                    Some((path.file_stem()?.to_str()?.to_string(), path))
+                })
+                .collect(),
+            None => pkg_json
+                .directories
+                .as_ref()
+                .and_then(|directories| directories.get("bin"))
+                .map(|dir| files::walk_files(&join_manifest_path(&self.root_path, dir)))
+                .unwrap_or_default()
+                .into_iter()
+                .filter_map(|path| {
+                    let command = path.file_stem()?.to_str()?.to_string();
+
+                    (!command.starts_with('.')).then_some((command, path))
+                })
+                .collect(),
+        }
+    }
+
+    /// Read the contents of [`PackageInfo::package_entry`]
+    ///
+    /// The IO error is returned as is, so a missing entry can be told from an unreadable
+    /// one.
+    pub fn read_entry(&self) -> io::Result<String> {
+        fs::read_to_string(&self.package_entry)
+    }
+
+    /// Get the `?query` or `#hash` suffix of the `module` or `main` value the entry was read
+    /// from; the suffix is stripped from [`PackageInfo::package_entry`]. An entry resolved
+    /// through `exports` has no suffix.
+    pub fn entry_suffix(&self) -> Option<&str> {
+        let pkg_json = &self.package_json;
+
+        if self.uses_exports() {
+            return None;
+        }
+
+        let entry = match pkg_json.module.as_ref().filter(|_| pkg_json.is_module()) {
+            Some(module) => module,
+            None => pkg_json.main.as_ref()?,
+        };
+
+        split_entry_suffix(entry).1
+    }
+
+    /// Whether the package installs any command, through `bin` or `directories.bin`.
+    pub fn has_bin(&self) -> bool {
+        !self.bin_paths().is_empty()
+    }
+
+    /// Whether the package declares an `exports` field, which blocks deep imports of
+    /// anything it does not export.
+    pub fn uses_exports(&self) -> bool {
+        self.package_json.exports.is_some()
+    }
+
+    /// List every target of the `exports` field as `(subpath, condition, path)`
+    ///
+    /// Nested conditions are joined with `.` (e.g. `node.import`) and targets outside any
+    /// condition are listed under `default`. Pattern subpaths are listed as declared,
+    /// with their `*` left in the path. The list is sorted.
+    pub fn all_export_targets(&self) -> Vec<(String, String, PathBuf)> {
+        let Some(exports) = self.package_json.exports.as_ref() else {
+            return vec![];
+        };
+        let mut targets: Vec<(String, String, PathBuf)> = exports::subpaths(exports)
+            .into_iter()
+            .filter_map(|subpath| {
+                let (value, _) = exports::get_export(exports, &subpath)?;
+                let targets = exports::condition_targets(value)
+                    .into_iter()
+                    .map(|(condition, target)| {
+                        (
+                            subpath.clone(),
+                            condition,
+                            join_manifest_path(&self.root_path, target),
+                        )
+                    })
+                    .collect::<Vec<_>>();
+
+                Some(targets)
+            })
+            .flatten()
+            .collect();
+
+        targets.sort();
+        targets
+    }
+
+    /// Get the runtime entry for `target`
+    ///
+    /// The root `exports` entry is resolved with `conditions`, or with
+    /// [`Target::conditions`] when `conditions` is empty. Without `exports`, browsers use
+    /// `browser` then `module`, React Native uses `react-native` then `browser`, and then
+    /// `main` or `index.*` is used.
+    pub fn entry_for(&self, target: Target, conditions: &[&str]) -> Option<PathBuf> {
+        let pkg_json = &self.package_json;
+        let conditions = match conditions.is_empty() {
+            true => target.conditions(),
+            false => conditions,
+        };
+
+        if let Some(exports) = pkg_json.exports.as_ref() {
+            return exports::resolve_export(exports, ".", conditions)
+                .map(|entry| join_manifest_path(&self.root_path, &entry));
+        }
+
+        let browser = pkg_json.browser.as_ref().and_then(Browser::entry);
+        let legacy_entry = match target {
+            Target::Browser => browser.or(pkg_json.module.as_deref()),
+            Target::ReactNative => pkg_json.react_native.as_deref().or(browser),
+            Target::Node | Target::Deno => None,
+        };
+
+        match legacy_entry.or(pkg_json.main.as_deref()) {
+            Some(entry) => resolve_main(&self.root_path, entry, pkg_json.is_module()),
+            None => probe_extensions(&self.root_path.join("index"), pkg_json.is_module()),
+        }
+    }
+
+    /// Get the first existing entry, trying in order:
+    ///
+    /// 1. the root `exports` entry, matched with [`Options::conditions`]
+    /// 2. `module`
+    /// 3. `main`, with extensions probed like `require` does
+    /// 4. `index.js` (or another `index.*` file for the package `type`)
+    pub fn best_entry(&self, options: &Options) -> Option<PathBuf> {
+        let pkg_json = &self.package_json;
+        let root = &self.root_path;
+        let is_file = |path: &PathBuf| path.is_file();
+
+        pkg_json
+            .exports
+            .as_ref()
+            .and_then(|exports| exports::resolve_export(exports, ".", &options.condition_list()))
+            .map(|entry| join_manifest_path(root, &entry))
+            .filter(is_file)
+            .or_else(|| {
+                pkg_json
+                    .module
+                    .as_ref()
+                    .map(|module| join_manifest_path(root, module))
+                    .filter(is_file)
+            })
+            .or_else(|| {
+                let main = pkg_json.main.as_ref()?;
+
+                resolve_main(root, main, pkg_json.is_module())
+            })
+            .or_else(|| probe_extensions(&root.join("index"), pkg_json.is_module()))
+    }
+
+    /// Get the entry named by an arbitrary top-level string field, e.g. `svelte` or `style`
+    ///
+    /// The path must exist, possibly once an extension is probed like for `main`; it uses
+    /// `/` separators with [`Options::posix_paths`].
+    pub fn custom_entry(&self, field: &str, options: &Options) -> Option<PathBuf> {
+        let manifest = serde_json::to_value(&self.package_json).ok()?;
+        let entry = manifest.get(field)?.as_str()?;
+        let path = resolve_main(&self.root_path, entry, self.package_json.is_module())?;
+
+        Some(match options.posix_paths {
+            true => to_posix_path(&path),
+            false => path,
+        })
+    }
+
+    /// Get the TypeScript declarations entry
+    ///
+    /// The root `exports` entry is resolved like [`PackageInfo::entry_for`], but preferring
+    /// the `types` condition; the `types` field is used when that yields no declaration file.
+    pub fn types_entry(&self) -> Option<PathBuf> {
+        get_types_entry_for(&self.root_path, &self.package_json)
+    }
+
+    /// Names declared in `dependencies`, `devDependencies`, `peerDependencies` and
+    /// `optionalDependencies`, combined.
+    pub fn all_dependency_names(&self) -> HashSet<&str> {
+        let pkg_json = &self.package_json;
+
+        [
+            &pkg_json.dependencies,
+            &pkg_json.dev_dependencies,
+            &pkg_json.peer_dependencies,
+            &pkg_json.optional_dependencies,
+        ]
+        .into_iter()
+        .flatten()
+        .flat_map(|dependencies| dependencies.keys().map(String::as_str))
+        .collect()
+    }
+
+    /// Get a clickable URL for the package home page
+    ///
+    /// `homepage`, or else the `repository` URL, is normalized: `github:`, `gitlab:` and
+    /// `bitbucket:` shorthands and bare `user/repo` paths point to the hosting site, and git
+    /// URLs are turned into `https://` URLs without their `.git` suffix.
+    ///
+    /// # Exmaple
+    /// ```
+    /// use npm_pkg::{get_package_info, Options};
+    ///
+    /// let homepage = |name| get_package_info(name, Options::default()).unwrap().homepage_url();
+    ///
+    /// assert_eq!(homepage("consola").as_deref(), Some("https://github.com/unjs/consola"));
+    /// assert_eq!(homepage("@jridgewell/sourcemap-codec").as_deref(), Some("https://github.com/jridgewell/sourcemap-codec"));
+    /// ```
+    pub fn homepage_url(&self) -> Option<String> {
+        let pkg_json = &self.package_json;
+
+        if let Some(homepage) = pkg_json.homepage.as_deref() {
+            return match homepage.starts_with("http://") || homepage.starts_with("https://") {
+                true => Some(homepage.to_string()),
+                false => to_https_url(homepage),
+            };
+        }
+
+        match pkg_json.repository.as_ref()? {
+            Repository::String(url) | Repository::Object { url, .. } => to_https_url(url),
+        }
+    }
+
+    /// Find the changelog in `root_path`, case-insensitively
+    ///
+    /// `CHANGELOG.md` is preferred over `CHANGELOG`, which is preferred over `HISTORY.md`.
+    pub fn changelog_path(&self) -> Option<PathBuf> {
+        let files: Vec<PathBuf> = fs::read_dir(&self.root_path)
+            .ok()?
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.is_file())
+            .collect();
+
+        ["changelog.md", "changelog", "history.md"]
+            .iter()
+            .find_map(|candidate| {
+                files.iter().find(|path| {
+                    path.file_name()
+                        .is_some_and(|name| name.to_string_lossy().to_lowercase() == *candidate)
+                })
+            })
+            .cloned()
+    }
+
+    /// Get a value below an unmodeled field of the manifest by a dotted path
+    ///
+    /// The first segment names a field of [`PackageJSON::extra`]; the following ones are
+    /// object keys, or indices into arrays.
+    ///
+    /// # Exmaple
+    /// ```
+    /// use npm_pkg::{get_package_info, Options};
+    ///
+    /// let pkg_info = get_package_info("consola", Options::default()).unwrap();
+    ///
+    /// assert_eq!(pkg_info.get_path("packageManager").and_then(|value| value.as_str()), Some("pnpm@8.6.5"));
+    /// assert_eq!(pkg_info.get_path("packageManager.version"), None);
+    /// ```
+    pub fn get_path(&self, dotted: &str) -> Option<&serde_json::Value> {
+        let mut segments = dotted.split('.');
+        let first = self.package_json.extra.get(segments.next()?)?;
+
+        segments.try_fold(first, |value, segment| match value {
+            serde_json::Value::Object(fields) => fields.get(segment),
+            serde_json::Value::Array(items) => items.get(segment.parse::<usize>().ok()?),
+            _ => None,
+        })
+    }
+
+    /// Whether both infos describe the same directory on disk, once symlinks are resolved,
+    /// e.g. two links to one pnpm store entry.
+    pub fn same_install(&self, other: &PackageInfo) -> bool {
+        let canonical = |pkg: &PackageInfo| {
+            fs::canonicalize(&pkg.root_path).unwrap_or_else(|_| pkg.root_path.clone())
+        };
+
+        canonical(self) == canonical(other)
+    }
+
+    /// Get the license as a single SPDX expression
+    ///
+    /// `license` is used as is, without enclosing parentheses. The deprecated `licenses`
+    /// array is joined with `OR`, as each of its items was an alternative. `UNLICENSED` and
+    /// `SEE LICENSE IN <file>` are not SPDX expressions and yield `None`.
+    ///
+    /// # Exmaple
+    /// ```
+    /// use npm_pkg::{PackageInfo, PackageJSON};
+    ///
+    /// let pkg_info = |json: &str| PackageInfo {
+    ///     package_json: serde_json::from_str::<PackageJSON>(json).unwrap(),
+    ///     ..Default::default()
+    /// };
+    ///
+    /// assert_eq!(pkg_info(r#"{ "license": "(MIT OR Apache-2.0)" }"#).license_expression().as_deref(), Some("MIT OR Apache-2.0"));
+    /// assert_eq!(pkg_info(r#"{ "license": { "type": "ISC", "url": "https://opensource.org/licenses/ISC" } }"#).license_expression().as_deref(), Some("ISC"));
+    /// assert_eq!(pkg_info(r#"{ "licenses": [{ "type": "MIT" }, { "type": "GPL-2.0" }] }"#).license_expression().as_deref(), Some("MIT OR GPL-2.0"));
+    /// ```
+    pub fn license_expression(&self) -> Option<String> {
+        let pkg_json = &self.package_json;
+        let expression = match pkg_json.license.as_ref() {
+            Some(license) => strip_enclosing_parens(license.id()?.trim()).to_string(),
+            None => {
+                let licenses: Vec<String> = pkg_json
+                    .extra
+                    .get("licenses")?
+                    .as_array()?
+                    .iter()
+                    .filter_map(|license| match license {
+                        serde_json::Value::String(id) => Some(id.clone()),
+                        license => Some(license.get("type")?.as_str()?.to_string()),
+                    })
+                    .map(|id| match id.contains(' ') {
+                        true => format!("({})", strip_enclosing_parens(&id)),
+                        false => id,
+                    })
+                    .collect();
+
+                licenses.join(" OR ")
+            }
+        };
+
+        let is_spdx = !expression.is_empty()
+            && expression != "UNLICENSED"
+            && !expression.starts_with("SEE LICENSE IN");
+
+        is_spdx.then_some(expression)
+    }
+
+    /// Get `dist.integrity`, only present in manifests that came from the registry.
+    pub fn integrity(&self) -> Option<&str> {
+        self.package_json.dist.as_ref()?.integrity.as_deref()
+    }
+
+    /// Get the `peerDependencies` ranges by name, empty when there are none.
+    pub fn peer_ranges(&self) -> HashMap<&str, &str> {
+        self.package_json
+            .peer_dependencies
+            .iter()
+            .flatten()
+            .map(|(name, range)| (name.as_str(), range.as_str()))
+            .collect()
+    }
+
+    /// Whether `peerDependenciesMeta` marks the peer `name` as optional.
+    pub fn is_optional_peer(&self, name: &str) -> bool {
+        self.package_json
+            .peer_dependencies_meta
+            .as_ref()
+            .and_then(|meta| meta.get(name)?.optional)
+            .unwrap_or(false)
+    }
+
+    /// Serialize to pretty JSON that is byte-stable across runs and machines
+    ///
+    /// Object keys are sorted, and paths are made relative to `root_path` (`"."` for the
+    /// root itself) with `/` separators. A `real_path` outside the root is kept whole.
+    pub fn to_canonical_json(&self) -> String {
+        let relative = |path: &Path| match path.strip_prefix(&self.root_path) {
+            Ok(relative) if relative.as_os_str().is_empty() => PathBuf::from("."),
+            Ok(relative) => to_posix_path(relative),
+            Err(_) => to_posix_path(path),
+        };
+        let canonical = PackageInfo {
+            root_path: PathBuf::from("."),
+            real_path: relative(&self.real_path),
+            package_json_path: relative(&self.package_json_path),
+            package_entry: relative(&self.package_entry),
+            ..self.clone()
+        };
+        let value = serde_json::to_value(canonical).map(sort_keys);
+
+        value
+            .and_then(|value| serde_json::to_string_pretty(&value))
+            .unwrap_or_default()
+    }
+
+    fn into_posix_paths(self) -> PackageInfo {
+        PackageInfo {
+            root_path: to_posix_path(&self.root_path),
+            real_path: to_posix_path(&self.real_path),
+            package_json_path: to_posix_path(&self.package_json_path),
+            package_entry: to_posix_path(&self.package_entry),
+            ..self
+        }
+    }
 }
 
 static CURRENT_DIR: LazyLock<PathBuf> = LazyLock::new(|| current_dir().unwrap());
@@ -76,31 +988,33 @@ static CURRENT_DIR: LazyLock<PathBuf> = LazyLock::new(|| current_dir().unwrap())
 /// # Exmaple
 /// ```
 /// use std::{env::current_dir, vec};
-/// use npm_pkg::{get_package_info, is_package_exists, Options, PackageInfo, PackageJSON};
-
+/// use npm_pkg::{get_package_info, is_package_exists, Browser, License, ModuleKind, Options, PackageInfo, PackageJSON, Repository};
+///
 /// let pkg_info = get_package_info("consola", Options::default());
 /// assert_eq!(pkg_info, Some(PackageInfo {
 /// name: String::from("consola"),
 /// version: String::from("3.2.3"),
 /// root_path: current_dir().unwrap().join("node_modules/consola"),
+/// real_path: current_dir().unwrap().join("node_modules/consola"),
 /// package_json_path: current_dir().unwrap().join("node_modules/consola/package.json"),
 /// package_entry: current_dir().unwrap().join("node_modules/consola/dist/index.mjs"),
+/// module_kind: ModuleKind::Esm,
 /// package_json: PackageJSON {
 ///     name: Some(String::from("consola")),
 ///     version: Some(String::from("3.2.3")),
 ///     description: Some(String::from("Elegant Console Wrapper")),
 ///     homepage: None,
 ///     keywords: Some(vec![String::from("console"), String::from("logger"), String::from("reporter"), String::from("elegant"), String::from("cli"), String::from("universal"), String::from("unified"), String::from("prompt"), String::from("clack"), String::from("format"), String::from("error"), String::from("stacktrace")]),
-///     license: Some(String::from("MIT")),
+///     license: Some(License::String(String::from("MIT"))),
 ///     private: None,
 ///     author: None,
 ///     files: Some(vec![String::from("dist"), String::from("lib"), String::from("*.d.ts")]),
 ///     r#type: Some(String::from("module")),
 ///     main: Some(String::from("./lib/index.cjs")),
 ///     module: Some(String::from("./dist/index.mjs")),
-///     exports: None,
+///     exports: Some(serde_json::from_str(r#"{ ".": { "node": { "types": "./dist/index.d.ts", "import": "./dist/index.mjs", "require": "./lib/index.cjs" }, "default": { "types": "./dist/browser.d.ts", "import": "./dist/browser.mjs" } }, "./browser": { "types": "./dist/browser.d.ts", "import": "./dist/browser.mjs" }, "./basic": { "node": { "types": "./dist/basic.d.ts", "import": "./dist/basic.mjs", "require": "./dist/basic.cjs" }, "default": { "types": "./dist/browser.d.ts", "import": "./dist/browser.mjs" } }, "./core": { "types": "./dist/core.d.ts", "import": "./dist/core.mjs", "require": "./dist/core.cjs" }, "./utils": { "types": "./dist/utils.d.ts", "import": "./dist/utils.mjs", "require": "./dist/utils.cjs" } }"#).unwrap()),
 ///     types: Some(String::from("./dist/index.d.ts")),
-///     browser: Some(String::from("./dist/browser.mjs")),
+///     browser: Some(Browser::String(String::from("./dist/browser.mjs"))),
 ///     bin: None,
 ///     scripts: Some(serde_json::from_str(r#"{ "build": "unbuild", "lint:fix": "eslint . --fix && prettier -w src examples test", "lint": "eslint . && prettier -c src examples test", "test": "pnpm lint && pnpm vitest run --coverage", "release": "pnpm test && pnpm build && changelogen --release --push && npm publish", "dev": "vitest" }"#).unwrap()),
 ///     dependencies: None,
@@ -108,52 +1022,350 @@ static CURRENT_DIR: LazyLock<PathBuf> = LazyLock::new(|| current_dir().unwrap())
 ///     peer_dependencies: None,
 ///     peer_dependencies_meta: None,
 ///    optional_dependencies: None,
-///     engines: Some(serde_json::from_str(r#"{"node": "^14.18.0 || >=16.10.0"}"#).unwrap())
-/// }
+///     engines: Some(serde_json::from_str(r#"{"node": "^14.18.0 || >=16.10.0"}"#).unwrap()),
+///     workspaces: None,
+///     publish_config: None,
+///     overrides: None,
+///     directories: None,
+///     extra: serde_json::from_str(r#"{ "packageManager": "pnpm@8.6.5" }"#).unwrap(),
+///     dist: None,
+///     react_native: None,
+///     imports: None,
+///     repository: Some(Repository::String(String::from("unjs/consola"))),
+///     bugs: None,
+///     contributors: None,
+///     funding: None,
+/// },
+/// raw_json: None
 /// }));
 /// ```
 pub fn get_package_info(name: &str, options: Options) -> Option<PackageInfo> {
+    try_get_package_info(name, options).ok()
+}
+
+/// Get npm package info like [`get_package_info`], telling why it could not be loaded
+///
+/// # Exmaple
+/// ```
+/// use npm_pkg::{try_get_package_info, Options, PackageError};
+///
+/// assert!(try_get_package_info("consola", Options::default()).is_ok());
+/// assert!(matches!(try_get_package_info("abc", Options::default()), Err(PackageError::NotFound { .. })));
+/// assert!(matches!(try_get_package_info("Not Valid", Options::default()), Err(PackageError::InvalidName { .. })));
+/// ```
+pub fn try_get_package_info(name: &str, options: Options) -> Result<PackageInfo, PackageError> {
+    let package_json_path = locate_package_json(name, &options)?;
+
+    try_load_package_info(name, package_json_path, &options)
+}
+
+/// Whether `name` is a valid npm package name, new or legacy, so that joining it to a
+/// `node_modules` cannot leave that directory.
+pub(crate) fn is_valid_name(name: &str) -> bool {
     let validate_result = validate(&name.to_string());
 
-    if !validate_result.valid_for_new_packages && !validate_result.valid_for_old_packages {
-        return None;
+    validate_result.valid_for_new_packages || validate_result.valid_for_old_packages
+}
+
+/// Find the `package.json` of package `name` like [`get_package_json_path`], telling why it
+/// could not be found.
+pub(crate) fn locate_package_json(name: &str, options: &Options) -> Result<PathBuf, PackageError> {
+    if !is_valid_name(name) {
+        return Err(PackageError::InvalidName {
+            name: name.to_string(),
+        });
     }
 
-    let package_json_path = get_package_json_path(name, &options);
+    get_package_json_path(name, options).ok_or_else(|| PackageError::NotFound {
+        name: name.to_string(),
+        searched: match options.is_allowed(name) {
+            true => node_modules_candidates(&lookup_dir(options), options).collect(),
+            false => vec![],
+        },
+    })
+}
 
-    package_json_path.as_ref()?;
+pub(crate) fn get_package_info_for(name: &str, options: &Options) -> Option<PackageInfo> {
+    let package_json_path = get_package_json_path(name, options)?;
 
-    let package_json = get_package_json(package_json_path.as_ref().unwrap().as_path());
+    load_package_info(name, package_json_path, options)
+}
 
-    let package_entry = get_package_entry(package_json_path.as_ref().unwrap().as_path());
+pub(crate) fn load_package_info(
+    name: &str,
+    package_json_path: PathBuf,
+    options: &Options,
+) -> Option<PackageInfo> {
+    try_load_package_info(name, package_json_path, options).ok()
+}
 
-    if package_json.is_none() || package_entry.is_none() {
-        return None;
-    }
+pub(crate) fn try_load_package_info(
+    name: &str,
+    package_json_path: PathBuf,
+    options: &Options,
+) -> Result<PackageInfo, PackageError> {
+    try_load_package_info_with(name, package_json_path, options, Required::VersionAndEntry)
+}
+
+/// What a `package.json` has to provide to be loaded into a [`PackageInfo`]
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum Required {
+    /// A `version` and a runtime entry, like [`get_package_info`] requires.
+    VersionAndEntry,
+    /// A `version`; without a runtime entry, [`PackageInfo::package_entry`] is empty.
+    Version,
+    /// Nothing; without a `version`, [`PackageInfo::version`] is empty too.
+    Nothing,
+}
+
+pub(crate) fn try_load_package_info_with(
+    name: &str,
+    package_json_path: PathBuf,
+    options: &Options,
+    required: Required,
+) -> Result<PackageInfo, PackageError> {
+    let (package_json, raw_json) = read_package_json_raw(&package_json_path)?;
 
-    Some(PackageInfo {
+    build_package_info(
+        name,
+        package_json_path,
+        package_json,
+        raw_json,
+        options,
+        required,
+    )
+}
+
+/// Make the [`PackageInfo`] of a `package.json` that has already been read.
+pub(crate) fn build_package_info(
+    name: &str,
+    package_json_path: PathBuf,
+    package_json: PackageJSON,
+    raw_json: String,
+    options: &Options,
+    required: Required,
+) -> Result<PackageInfo, PackageError> {
+    let root_path = package_json_path.parent().unwrap().to_path_buf();
+    let version = match (package_json.version.clone(), required) {
+        (Some(version), _) => version,
+        (None, Required::Nothing) => String::new(),
+        (None, _) => {
+            return Err(PackageError::MissingVersion {
+                path: package_json_path,
+            })
+        }
+    };
+    let package_entry = match get_package_entry(&root_path, &package_json, options) {
+        Some(package_entry) => package_entry,
+        None if required != Required::VersionAndEntry => PathBuf::new(),
+        None => {
+            return Err(PackageError::MissingEntry {
+                path: package_json_path,
+            })
+        }
+    };
+    let module_kind = match package_entry
+        .extension()
+        .and_then(|extension| extension.to_str())
+    {
+        Some("mjs") => ModuleKind::Esm,
+        Some("cjs") => ModuleKind::Cjs,
+        _ if package_json.is_module() => ModuleKind::Esm,
+        _ => ModuleKind::Cjs,
+    };
+
+    let real_path = if options.follow_symlinks {
+        fs::canonicalize(&root_path).unwrap_or_else(|_| root_path.clone())
+    } else {
+        root_path.clone()
+    };
+
+    let pkg_info = PackageInfo {
         name: name.to_string(),
-        version: package_json.clone().unwrap().version?,
-        root_path: package_json_path
-            .as_ref()
-            .unwrap()
-            .parent()
-            .unwrap()
-            .to_path_buf(),
-        package_entry: package_entry.unwrap(),
-        package_json_path: package_json_path.unwrap(),
-        package_json: package_json.unwrap(),
+        version,
+        root_path,
+        real_path,
+        package_entry,
+        module_kind,
+        package_json_path,
+        package_json,
+        raw_json: options.retain_raw.then_some(raw_json),
+    };
+
+    Ok(match options.posix_paths {
+        true => pkg_info.into_posix_paths(),
+        false => pkg_info,
     })
 }
 
+/// Get the installed version of package `name`, as [`PackageInfo::version`] would be
+///
+/// Only `version` is deserialized, so this is cheaper than [`get_package_info`] when
+/// nothing else is needed.
+///
+/// # Exmaple
+/// ```
+/// use npm_pkg::{get_package_version, Options};
+///
+/// assert_eq!(get_package_version("consola", &Options::default()).as_deref(), Some("3.2.3"));
+/// assert_eq!(get_package_version("abc", &Options::default()), None);
+/// ```
+pub fn get_package_version(name: &str, options: &Options) -> Option<String> {
+    #[derive(Deserialize)]
+    struct VersionOnly {
+        #[serde(default, deserialize_with = "deserialize_relaxed_version")]
+        version: Option<String>,
+    }
+
+    let json = fs::read_to_string(get_package_json_path(name, options)?).ok()?;
+
+    serde_json::from_str::<VersionOnly>(&json).ok()?.version
+}
+
+/// Get the TypeScript declarations entry of package `name`, see [`PackageInfo::types_entry`]
+///
+/// Unlike [`get_package_info`], this does not require the package to have a runtime entry,
+/// so the types of a package whose `main` is missing are still found.
+pub fn get_types_entry(name: &str, options: &Options) -> Option<PathBuf> {
+    let package_json_path = get_package_json_path(name, options)?;
+    let pkg_json = get_package_json(&package_json_path)?;
+
+    get_types_entry_for(package_json_path.parent()?, &pkg_json)
+}
+
+fn get_types_entry_for(root: &Path, pkg_json: &PackageJSON) -> Option<PathBuf> {
+    pkg_json
+        .exports
+        .as_ref()
+        .and_then(|exports| {
+            exports::resolve_root_target(exports, TYPES_CONDITIONS, &is_declaration_file)
+        })
+        .map(String::from)
+        .or_else(|| pkg_json.types.clone())
+        .map(|entry| join_manifest_path(root, &entry))
+}
+
+/// Find the `package.json` of package `name` in `<cwd>/node_modules`, then in the
+/// `node_modules` of each ancestor of `cwd`, like Node does
+///
+/// With [`Options::from`], the lookup starts there instead of `cwd`. Names that are not
+/// valid package names, such as `../foo`, are never looked up.
 pub fn get_package_json_path(name: &str, options: &Options) -> Option<PathBuf> {
-    let id = format!("node_modules/{}/package.json", name);
-    let pkg_json_path = resolve(&id, options);
+    if !is_valid_name(name) || !options.is_allowed(name) {
+        return None;
+    }
+
+    let id = format!("{}/{}/package.json", options.modules_dir_name(), name);
+    let pkg_json_path = resolve_upward(&id, options);
+
+    pkg_json_path.ok()
+}
+
+/// Find `node_modules/<name>/package.json` from `dir` upwards, skipping directories that
+/// are themselves named `node_modules`.
+pub(crate) fn lookup_package_json(dir: &Path, name: &str, options: &Options) -> Option<PathBuf> {
+    if !is_valid_name(name) || !options.is_allowed(name) {
+        return None;
+    }
+
+    node_modules_candidates(dir, options)
+        .map(|node_modules| node_modules.join(name).join("package.json"))
+        .find(|path| path.is_file())
+}
+
+/// Get every existing `node_modules` directory from `cwd` (or [`Options::from`]) up to the
+/// filesystem root, in the order packages are looked up
+///
+/// # Exmaple
+/// ```
+/// use std::env::current_dir;
+/// use npm_pkg::{node_modules_roots, Options};
+///
+/// let roots = node_modules_roots(&Options::default());
+///
+/// assert_eq!(roots[0], current_dir().unwrap().join("node_modules"));
+/// ```
+pub fn node_modules_roots(options: &Options) -> Vec<PathBuf> {
+    node_modules_candidates(&lookup_dir(options), options)
+        .filter(|node_modules| node_modules.is_dir())
+        .collect()
+}
+
+/// Get every directory package `name` is installed in, in the order they are looked up
+///
+/// The copy `get_package_info` would pick comes first, then shadowed copies further up
+/// the tree, then copies nested in the `node_modules` of other packages below the first
+/// `node_modules`, sorted by path.
+pub fn resolve_all_locations(name: &str, options: &Options) -> Vec<PathBuf> {
+    locations_from(&lookup_dir(options), name, options)
+}
 
-    match pkg_json_path {
-        Ok(pkg_json_path) => Some(pkg_json_path),
-        Err(_) => None,
+/// [`resolve_all_locations`], looking packages up from `dir`.
+pub(crate) fn locations_from(dir: &Path, name: &str, options: &Options) -> Vec<PathBuf> {
+    if !is_valid_name(name) || !options.is_allowed(name) {
+        return vec![];
     }
+
+    let mut locations: Vec<PathBuf> = node_modules_candidates(dir, options)
+        .map(|node_modules| node_modules.join(name))
+        .filter(|dir| dir.join("package.json").is_file())
+        .collect();
+    let mut nested: Vec<PathBuf> = node_modules_candidates(dir, options)
+        .find(|node_modules| node_modules.is_dir())
+        .map(|node_modules| installed::scan_recursive(&node_modules, options))
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|pkg| pkg.name == name && !locations.contains(&pkg.root_path))
+        .map(|pkg| pkg.root_path)
+        .collect();
+
+    nested.sort();
+    locations.extend(nested);
+    locations
+}
+
+fn node_modules_candidates<'a>(
+    dir: &'a Path,
+    options: &'a Options,
+) -> impl Iterator<Item = PathBuf> + 'a {
+    search_dirs(dir)
+        .filter(|dir| !dir.ends_with(options.modules_dir_name()))
+        .map(|dir| dir.join(options.modules_dir_name()))
+}
+
+/// `dir` and its ancestors, nearest first, skipping directories named `node_modules`
+///
+/// A relative `dir` is resolved against the current directory before walking up, so
+/// `../other` never visits the current directory. Directories inside the current one
+/// are returned relative to it, with the current directory itself as the empty path.
+pub(crate) fn search_dirs(dir: &Path) -> impl Iterator<Item = PathBuf> + '_ {
+    let absolute =
+        CURRENT_DIR
+            .join(dir)
+            .components()
+            .fold(PathBuf::new(), |mut path, component| {
+                match component {
+                    Component::CurDir => {}
+                    Component::ParentDir => {
+                        path.pop();
+                    }
+                    component => path.push(component),
+                }
+                path
+            });
+    let relative_to = dir.is_relative().then_some(CURRENT_DIR.as_path());
+
+    absolute
+        .ancestors()
+        .filter(|dir| !dir.ends_with("node_modules"))
+        .map(|dir| {
+            relative_to
+                .and_then(|current_dir| dir.strip_prefix(current_dir).ok())
+                .unwrap_or(dir)
+                .to_path_buf()
+        })
+        .collect::<Vec<_>>()
+        .into_iter()
 }
 
 /// Get npm package info
@@ -172,57 +1384,280 @@ pub fn is_package_exists(name: &str, options: &Options) -> bool {
     pkg_json_path.is_some()
 }
 
-fn get_package_json(path: &Path) -> Option<PackageJSON> {
-    let json = fs::read_to_string(path);
+/// Get the info of the root project itself, read from the `package.json` in `cwd`
+///
+/// The returned `name` is the one declared in the manifest. A root manifest needs neither
+/// a `version` nor an entry, as in a monorepo; [`PackageInfo::version`] and
+/// [`PackageInfo::package_entry`] are empty then.
+pub fn get_self_info(options: &Options) -> Option<PackageInfo> {
+    let package_json_path = resolve("package.json", options).ok()?;
+    let name = get_package_json(&package_json_path)?.name?;
+
+    try_load_package_info_with(&name, package_json_path, options, Required::Nothing).ok()
+}
+
+/// Find the closest `package.json` at or above `start`
+///
+/// When `start` is a file the search begins in its containing directory.
+pub fn find_nearest_package_json(start: &Path) -> Option<PathBuf> {
+    let dir = if start.is_file() {
+        start.parent()?
+    } else {
+        start
+    };
+
+    dir.ancestors()
+        .map(|dir| dir.join("package.json"))
+        .find(|path| path.is_file())
+}
+
+pub(crate) fn get_package_json(path: &Path) -> Option<PackageJSON> {
+    read_package_json(path).ok()
+}
+
+pub(crate) fn read_package_json(path: &Path) -> Result<PackageJSON, PackageError> {
+    read_package_json_raw(path).map(|(package_json, _)| package_json)
+}
+
+/// Read and parse a `package.json`, also returning its text.
+fn read_package_json_raw(path: &Path) -> Result<(PackageJSON, String), PackageError> {
+    let json = fs::read_to_string(path).map_err(|source| PackageError::ReadFailed {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    let package_json = serde_json::from_str(&json).map_err(|source| PackageError::ParseFailed {
+        path: path.to_path_buf(),
+        source,
+    })?;
 
-    match json {
-        Ok(json) => Some(serde_json::from_str(&json).unwrap()),
-        Err(_) => None,
+    Ok((package_json, json))
+}
+
+/// Get the entry of a package, trying in order:
+///
+/// 1. the root `exports` entry, matched with [`Options::conditions`]; with `exports` set,
+///    nothing else is tried, as Node does not fall back to `main` either
+/// 2. `module`, only when `type` is `"module"`; a `"commonjs"` or missing `type` skips it
+/// 3. `main`, with extensions probed like `require` does
+/// 4. `index.*`
+pub(crate) fn get_package_entry(
+    root: &Path,
+    pkg_json: &PackageJSON,
+    options: &Options,
+) -> Option<PathBuf> {
+    if let Some(exports) = pkg_json.exports.as_ref() {
+        exports::resolve_export(exports, ".", &options.condition_list())
+            .map(|root_entry| join_manifest_path(root, &root_entry))
+    } else if let Some(module) = pkg_json.module.as_ref().filter(|_| pkg_json.is_module()) {
+        Some(join_manifest_path(root, split_entry_suffix(module).0))
+    } else if let Some(main) = pkg_json.main.as_ref() {
+        resolve_main(root, main, pkg_json.is_module())
+    } else {
+        probe_extensions(&root.join("index"), pkg_json.is_module())
     }
 }
 
-fn get_package_entry(path: &Path) -> Option<PathBuf> {
-    let pkg_json = get_package_json(path);
+/// Turn a repository URL or shorthand into an `https://` URL.
+fn to_https_url(spec: &str) -> Option<String> {
+    let spec = spec.trim();
+    let spec = spec.strip_prefix("git+").unwrap_or(spec);
+    let hosts = [
+        ("github:", "github.com"),
+        ("gitlab:", "gitlab.com"),
+        ("bitbucket:", "bitbucket.org"),
+    ];
+    let (host, path) = if let Some((host, path)) = hosts
+        .iter()
+        .find_map(|(prefix, host)| Some((*host, spec.strip_prefix(prefix)?)))
+    {
+        (host, path)
+    } else if let Some((_, rest)) = spec.split_once("://") {
+        rest.trim_start_matches("git@").split_once('/')?
+    } else if let Some(rest) = spec.strip_prefix("git@") {
+        rest.split_once(':')?
+    } else if spec.split('/').count() == 2 && !spec.starts_with('.') && !spec.contains(':') {
+        ("github.com", spec)
+    } else {
+        return None;
+    };
+
+    Some(format!(
+        "https://{}/{}",
+        host,
+        path.trim_end_matches('/').trim_end_matches(".git")
+    ))
+}
 
-    if let Some(pkg_json) = pkg_json {
-        let root = path.parent().unwrap();
+/// Split a `?query` or `#hash` suffix, which some generated manifests add to entry fields,
+/// off a path.
+fn split_entry_suffix(entry: &str) -> (&str, Option<&str>) {
+    match entry.find(['?', '#']) {
+        Some(index) => (&entry[..index], Some(&entry[index..])),
+        None => (entry, None),
+    }
+}
 
-        if pkg_json.r#type.is_some_and(|t| t == "module") && pkg_json.module.is_some() {
-            Some(root.join(pkg_json.module.unwrap()))
-        } else if pkg_json
-            .exports
-            .as_ref()
-            .is_some_and(|exports| exports.contains_key("."))
-        {
-            let exports = pkg_json.exports.unwrap();
-            let root_entry = exports.get(".").unwrap();
-
-            match root_entry {
-                ExportValue::String(root_entry) => Some(root.join(root_entry)),
-                ExportValue::HashMap(root_entry) => {
-                    if root_entry.get("import").is_some() {
-                        Some(root.join(root_entry.get("import").unwrap()))
-                    } else {
-                        Some(root.join(root_entry.get("require").unwrap()))
-                    }
-                }
-            }
-        } else {
-            Some(root.join(pkg_json.main.as_ref().unwrap()))
+/// Join the manifest-relative `path` onto `root`
+///
+/// `.` segments such as a leading `./` are dropped, and `path` is split on `/` so the
+/// result uses the separator of the target OS.
+pub(crate) fn join_manifest_path(root: &Path, path: &str) -> PathBuf {
+    path.split(['/', std::path::MAIN_SEPARATOR])
+        .filter(|segment| !segment.is_empty() && *segment != ".")
+        .fold(root.to_path_buf(), |joined, segment| joined.join(segment))
+}
+
+/// Split `@scope/name` at its first `/`; anything else is treated as unscoped.
+pub(crate) fn split_scope(name: &str) -> (Option<&str>, &str) {
+    match name.split_once('/') {
+        Some((scope, rest)) if scope.len() > 1 && scope.starts_with('@') && !rest.is_empty() => {
+            (Some(scope), rest)
         }
+        _ => (None, name),
+    }
+}
+
+fn is_declaration_file(path: &str) -> bool {
+    [".d.ts", ".d.mts", ".d.cts"]
+        .iter()
+        .any(|extension| path.ends_with(extension))
+}
+
+/// Resolve a `main`-style path the way `require` does: the file itself, then with an
+/// extension appended (`"main": "index"`), then as a directory containing `index.*`.
+pub(crate) fn resolve_main(root: &Path, main: &str, is_module: bool) -> Option<PathBuf> {
+    let path = join_manifest_path(root, split_entry_suffix(main).0);
+
+    if path.is_file() {
+        return Some(path);
+    }
+
+    probe_extensions(&path, is_module).or_else(|| probe_extensions(&path.join("index"), is_module))
+}
+
+/// Find the first existing file among `base` with each candidate extension appended.
+///
+/// `.mjs` is tried before `.cjs` for ESM packages and the other way around for CommonJS,
+/// so an ambiguous `index` resolves to the file matching the package `type`.
+pub(crate) fn probe_extensions(base: &Path, is_module: bool) -> Option<PathBuf> {
+    let extensions: &[&str] = if is_module {
+        &["js", "mjs", "cjs", "json", "node"]
     } else {
-        None
+        &["js", "cjs", "mjs", "json", "node"]
+    };
+
+    extensions
+        .iter()
+        .map(|extension| {
+            let mut path = base.as_os_str().to_os_string();
+            path.push(".");
+            path.push(extension);
+            PathBuf::from(path)
+        })
+        .find(|path| path.is_file())
+}
+
+/// Strip parentheses enclosing a whole expression, e.g. `(MIT OR ISC)`.
+fn strip_enclosing_parens(expression: &str) -> &str {
+    let Some(inner) = expression
+        .strip_prefix('(')
+        .and_then(|inner| inner.strip_suffix(')'))
+    else {
+        return expression;
+    };
+    let mut depth = 0;
+
+    for c in inner.chars() {
+        match c {
+            '(' => depth += 1,
+            ')' if depth == 0 => return expression,
+            ')' => depth -= 1,
+            _ => {}
+        }
     }
+
+    inner.trim()
 }
 
-fn resolve(name: &str, options: &Options) -> Result<PathBuf, String> {
+/// Rebuild every object of `value` with its keys in sorted order.
+fn sort_keys(value: serde_json::Value) -> serde_json::Value {
+    match value {
+        serde_json::Value::Object(map) => {
+            let mut entries: Vec<(String, serde_json::Value)> = map.into_iter().collect();
+
+            entries.sort_by(|a, b| a.0.cmp(&b.0));
+            serde_json::Value::Object(
+                entries
+                    .into_iter()
+                    .map(|(key, value)| (key, sort_keys(value)))
+                    .collect(),
+            )
+        }
+        serde_json::Value::Array(items) => {
+            serde_json::Value::Array(items.into_iter().map(sort_keys).collect())
+        }
+        value => value,
+    }
+}
+
+fn to_posix_path(path: &Path) -> PathBuf {
+    PathBuf::from(
+        path.to_string_lossy()
+            .replace(std::path::MAIN_SEPARATOR, "/"),
+    )
+}
+
+pub(crate) fn cwd(options: &Options) -> PathBuf {
     let cwd = match options.cwd {
         Some(cwd) => Path::new(cwd),
         None => CURRENT_DIR.as_path(),
     };
-    let id = cwd.join(name);
 
-    if id.try_exists().unwrap() {
+    match &options.anchor {
+        Anchor::Cwd => cwd.to_path_buf(),
+        Anchor::GitRoot => search_dirs(cwd)
+            .find(|dir| dir.join(".git").exists())
+            .map(|dir| match dir.as_os_str().is_empty() {
+                true => CURRENT_DIR.clone(),
+                false => dir,
+            })
+            .unwrap_or_else(|| cwd.to_path_buf()),
+        Anchor::Custom(dir) => dir.clone(),
+    }
+}
+
+/// Look for `name` relative to the directory lookups start from (see [`Options::from`])
+/// and to each ancestor that is not itself a `node_modules` directory, nearest first.
+pub(crate) fn resolve_upward(name: &str, options: &Options) -> Result<PathBuf, String> {
+    let start = lookup_dir(options);
+    let found = search_dirs(&start)
+        .map(|dir| dir.join(name))
+        .find(|id| id.try_exists().unwrap_or(false));
+
+    found.ok_or_else(|| format!("Cannot find module {} from {:?}", name, start.join(name)))
+}
+
+/// The directory package lookups start from: [`Options::from`], or its parent when it is
+/// not a directory, else `cwd`.
+pub(crate) fn lookup_dir(options: &Options) -> PathBuf {
+    match options.from {
+        Some(from) => start_dir(from),
+        None => cwd(options),
+    }
+}
+
+/// `from`, or its parent when it is not a directory.
+pub(crate) fn start_dir(from: &Path) -> PathBuf {
+    match from.is_dir() {
+        true => from.to_path_buf(),
+        false => from.parent().unwrap_or(from).to_path_buf(),
+    }
+}
+
+pub(crate) fn resolve(name: &str, options: &Options) -> Result<PathBuf, String> {
+    let id = cwd(options).join(name);
+
+    if id.try_exists().unwrap_or(false) {
         Ok(id)
     } else {
         Err(format!("Cannot find module {} from {:?}", name, id))
@@ -0,0 +1,81 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::PackageJSON;
+
+/// A value of the `overrides` field
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(untagged)]
+pub enum OverrideValue {
+    /// The version to use, or `$name` to reuse the root's own dependency spec.
+    Version(String),
+    /// Overrides that only apply below the keyed package; `"."` overrides the package itself.
+    Nested(HashMap<String, OverrideValue>),
+}
+
+impl PackageJSON {
+    /// Get the version spec npm would install for dependency `name` requested as `range`
+    ///
+    /// `ancestors` are the names of the packages on the path from this (root) manifest to
+    /// the dependent, outermost first. Overrides nested under an ancestor apply anywhere
+    /// below it and take precedence over shallower ones; a `"."` key overrides the package
+    /// it is nested under, never a dependency literally named `.`.
+    pub fn apply_overrides(&self, ancestors: &[&str], name: &str, range: &str) -> String {
+        let Some(overrides) = self.overrides.as_ref() else {
+            return range.to_string();
+        };
+
+        if name == "." {
+            return range.to_string();
+        }
+
+        let mut scopes = vec![overrides];
+
+        for ancestor in ancestors.iter().filter(|ancestor| **ancestor != ".") {
+            let nested: Vec<&HashMap<String, OverrideValue>> = scopes
+                .iter()
+                .filter_map(|scope| match scope.get(*ancestor) {
+                    Some(OverrideValue::Nested(nested)) => Some(nested),
+                    _ => None,
+                })
+                .collect();
+
+            scopes.extend(nested);
+        }
+
+        let version = scopes
+            .iter()
+            .rev()
+            .find_map(|scope| match scope.get(name)? {
+                OverrideValue::Version(version) => Some(version),
+                OverrideValue::Nested(nested) => match nested.get(".")? {
+                    OverrideValue::Version(version) => Some(version),
+                    OverrideValue::Nested(_) => None,
+                },
+            });
+
+        match version {
+            Some(version) => self.dereference_override(version),
+            None => range.to_string(),
+        }
+    }
+
+    /// Resolve a `$name` override to the root's own spec for `name`.
+    fn dereference_override(&self, version: &str) -> String {
+        let Some(reference) = version.strip_prefix('$') else {
+            return version.to_string();
+        };
+
+        [
+            &self.dependencies,
+            &self.dev_dependencies,
+            &self.peer_dependencies,
+        ]
+        .into_iter()
+        .flatten()
+        .find_map(|dependencies| dependencies.get(reference))
+        .cloned()
+        .unwrap_or_else(|| version.to_string())
+    }
+}
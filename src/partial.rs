@@ -0,0 +1,119 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde_json::{Map, Value};
+
+use crate::{get_package_entry, get_package_json_path, Options, PackageError, PackageJSON};
+
+/// Whatever could be read of a package that may be broken
+#[derive(Debug)]
+pub struct PartialPackageInfo {
+    pub name: String,
+    pub version: Option<String>,
+    pub root_path: PathBuf,
+    pub package_json_path: PathBuf,
+    pub package_entry: Option<PathBuf>,
+    /// The manifest without any field that failed to parse.
+    pub package_json: Option<PackageJSON>,
+    /// Every problem met while loading the package.
+    pub problems: Vec<PackageError>,
+    /// Quirks that were tolerated, such as a numeric `version`.
+    pub warnings: Vec<String>,
+}
+
+/// Get as much info as possible about an installed package
+///
+/// Unlike [`crate::get_package_info`], a missing version or entry, or fields of the wrong
+/// shape, are recorded in [`PartialPackageInfo::problems`] instead of discarding the
+/// package. Returns `None` only when the package cannot be found.
+pub fn get_partial_package_info(name: &str, options: &Options) -> Option<PartialPackageInfo> {
+    let package_json_path = get_package_json_path(name, options)?;
+    let root_path = package_json_path.parent()?.to_path_buf();
+    let mut problems = vec![];
+    let mut warnings = vec![];
+    let package_json = read_lenient(&package_json_path, &mut problems, &mut warnings);
+    let version = package_json.as_ref().and_then(|pkg| pkg.version.clone());
+    let package_entry = package_json
+        .as_ref()
+        .and_then(|pkg| get_package_entry(&root_path, pkg, options));
+
+    if package_json.is_some() && version.is_none() {
+        problems.push(PackageError::MissingVersion {
+            path: package_json_path.clone(),
+        });
+    }
+
+    if package_json.is_some() && package_entry.is_none() {
+        problems.push(PackageError::MissingEntry {
+            path: package_json_path.clone(),
+        });
+    }
+
+    Some(PartialPackageInfo {
+        name: name.to_string(),
+        version,
+        root_path,
+        package_json_path,
+        package_entry,
+        package_json,
+        problems,
+        warnings,
+    })
+}
+
+/// Parse a manifest, dropping the fields that do not deserialize.
+fn read_lenient(
+    path: &Path,
+    problems: &mut Vec<PackageError>,
+    warnings: &mut Vec<String>,
+) -> Option<PackageJSON> {
+    let json = match fs::read_to_string(path) {
+        Ok(json) => json,
+        Err(source) => {
+            problems.push(PackageError::ReadFailed {
+                path: path.to_path_buf(),
+                source,
+            });
+            return None;
+        }
+    };
+    let mut fields: Map<String, Value> = match serde_json::from_str(&json) {
+        Ok(fields) => fields,
+        Err(source) => {
+            problems.push(PackageError::ParseFailed {
+                path: path.to_path_buf(),
+                source,
+            });
+            return None;
+        }
+    };
+    let mut invalid: Vec<String> = vec![];
+
+    if let Some(version @ (Value::Number(_) | Value::Bool(_))) = fields.get("version") {
+        warnings.push(format!(
+            "`version` is not a string, read {} as \"{}\"",
+            version, version
+        ));
+    }
+
+    for (field, value) in &fields {
+        let single = Map::from_iter([(field.clone(), value.clone())]);
+
+        if let Err(source) = serde_json::from_value::<PackageJSON>(Value::Object(single)) {
+            invalid.push(field.clone());
+            problems.push(PackageError::InvalidField {
+                path: path.to_path_buf(),
+                field: field.clone(),
+                source,
+            });
+        }
+    }
+
+    for field in invalid {
+        fields.remove(&field);
+    }
+
+    serde_json::from_value(Value::Object(fields)).ok()
+}
@@ -0,0 +1,55 @@
+use std::fs;
+
+use crate::{
+    is_valid_name,
+    range::{compare_versions, parse_version},
+    resolve, try_load_package_info, Options, PackageInfo,
+};
+
+/// Load package `name` from pnpm's virtual store, `node_modules/.pnpm/<name>@<version>`,
+/// bypassing the top-level symlinks
+///
+/// With [`Options::modules_dir`] set, the store is looked up in that directory instead.
+///
+/// Scoped names are stored with `+` in place of `/`, and the peer suffix pnpm appends to a
+/// store directory (`_peer@1.0.0` or `(peer@1.0.0)`) is ignored. Without `version` the
+/// highest stored semver version is used, so stores of local or git dependencies such as
+/// `foo@file+vendor+foo` are never picked.
+pub fn resolve_via_pnpm_store(
+    name: &str,
+    version: Option<&str>,
+    options: &Options,
+) -> Option<PackageInfo> {
+    if !is_valid_name(name) || !options.is_allowed(name) {
+        return None;
+    }
+
+    let modules_dir = options.modules_dir_name();
+    let store = resolve(&format!("{}/.pnpm", modules_dir), options).ok()?;
+    let prefix = format!("{}@", name.replace('/', "+"));
+    let mut candidates: Vec<(String, String)> = fs::read_dir(&store)
+        .ok()?
+        .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+        .filter_map(|dir_name| {
+            let stored = dir_name.strip_prefix(&prefix)?;
+            let stored = stored.split(['_', '(']).next()?.to_string();
+
+            Some((stored, dir_name))
+        })
+        .filter(|(stored, _)| match version {
+            Some(version) => version == stored,
+            None => parse_version(stored).is_some(),
+        })
+        .collect();
+
+    candidates.sort_by(|a, b| compare_versions(&a.0, &b.0));
+
+    let (_, dir_name) = candidates.pop()?;
+    let package_json_path = store
+        .join(dir_name)
+        .join(modules_dir)
+        .join(name)
+        .join("package.json");
+
+    try_load_package_info(name, package_json_path, options).ok()
+}
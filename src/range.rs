@@ -0,0 +1,124 @@
+use std::cmp::Ordering;
+
+use semver::{Version, VersionReq};
+
+use crate::{get_package_version, Options, PackageInfo};
+
+/// Whether the installed version of package `name` satisfies the npm `range`
+///
+/// Prerelease versions only match a range with a prerelease on the same
+/// `major.minor.patch`, like npm. Only the manifest's `version` is read, so packages
+/// without a runtime entry are checked too. Returns `None` when the package is not found
+/// or either the version or the range is not valid semver.
+///
+/// # Exmaple
+/// ```
+/// use npm_pkg::{package_satisfies, Options};
+///
+/// let options = Options::default();
+///
+/// assert_eq!(package_satisfies("consola", "^3.0.0 || ^2.0.0", &options), Some(true));
+/// assert_eq!(package_satisfies("consola", "~2.15.0", &options), Some(false));
+/// assert_eq!(package_satisfies("abc", "*", &options), None);
+/// ```
+pub fn package_satisfies(name: &str, range: &str, options: &Options) -> Option<bool> {
+    satisfies(&get_package_version(name, options)?, range)
+}
+
+/// Whether the concrete `version` of `engine` (e.g. `node`) satisfies the range the
+/// package declares in `engines`
+///
+/// Returns `None` when the package does not declare `engine`, or when either the version
+/// or the range is not valid semver.
+///
+/// # Exmaple
+/// ```
+/// use npm_pkg::{engine_satisfies, get_package_info, Options};
+///
+/// let pkg_info = get_package_info("consola", Options::default()).unwrap();
+///
+/// assert_eq!(engine_satisfies(&pkg_info, "node", "16.10.0"), Some(true));
+/// assert_eq!(engine_satisfies(&pkg_info, "node", "15.0.0"), Some(false));
+/// assert_eq!(engine_satisfies(&pkg_info, "npm", "9.0.0"), None);
+/// ```
+pub fn engine_satisfies(info: &PackageInfo, engine: &str, version: &str) -> Option<bool> {
+    let range = info.package_json.engines.as_ref()?.get(engine)?;
+
+    satisfies(version, range)
+}
+
+/// Whether `version` satisfies the npm `range`
+///
+/// npm's syntax is translated to the `semver` crate's: `||` unions are tested branch by
+/// branch, space-separated comparators are joined, and `A - B` becomes `>=A, <=B`.
+/// Returns `None` when either side does not parse.
+pub(crate) fn satisfies(version: &str, range: &str) -> Option<bool> {
+    let version = parse_version(version)?;
+    let reqs: Vec<VersionReq> = range
+        .split("||")
+        .map(|branch| VersionReq::parse(&to_semver_req(branch)).ok())
+        .collect::<Option<_>>()?;
+
+    Some(reqs.iter().any(|req| req.matches(&version)))
+}
+
+/// Parse `version` as semver, allowing surrounding whitespace and a leading `v` or `=`.
+pub(crate) fn parse_version(version: &str) -> Option<Version> {
+    Version::parse(strip_version_prefix(version.trim())).ok()
+}
+
+/// Order versions by semver precedence; versions that do not parse sort after the others,
+/// by their text.
+pub(crate) fn compare_versions(a: &str, b: &str) -> Ordering {
+    match (parse_version(a), parse_version(b)) {
+        (Some(a), Some(b)) => a.cmp(&b),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => a.cmp(b),
+    }
+}
+
+fn to_semver_req(branch: &str) -> String {
+    let branch = branch.trim();
+
+    if branch.is_empty() || branch == "x" || branch == "X" {
+        return String::from("*");
+    }
+
+    if let Some((from, to)) = branch.split_once(" - ") {
+        return format!(
+            ">={}, <={}",
+            strip_version_prefix(from.trim()),
+            strip_version_prefix(to.trim())
+        );
+    }
+
+    let mut comparators: Vec<String> = vec![];
+    let mut operator = String::new();
+
+    for token in branch.split_whitespace() {
+        if token.chars().all(|c| "<>=~^".contains(c)) {
+            operator.push_str(token);
+            continue;
+        }
+
+        let split = token.find(|c| !"<>=~^".contains(c)).unwrap_or(0);
+        let (token_operator, version) = token.split_at(split);
+
+        operator.push_str(token_operator);
+
+        // A bare version is exact in npm but a caret requirement in Cargo.
+        if operator.is_empty() && !version.contains(['x', 'X', '*']) {
+            operator.push('=');
+        }
+
+        comparators.push(format!("{}{}", operator, strip_version_prefix(version)));
+        operator.clear();
+    }
+
+    comparators.join(", ")
+}
+
+fn strip_version_prefix(version: &str) -> &str {
+    version.trim_start_matches(['v', '='])
+}
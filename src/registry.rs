@@ -0,0 +1,90 @@
+use std::{collections::HashMap, fs};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{cwd, search_dirs, Options, PackageJSON};
+
+const DEFAULT_REGISTRY: &str = "https://registry.npmjs.org/";
+
+/// Get the registry packages of `scope` (`@scope` or `scope`) are fetched from
+///
+/// Every `.npmrc` from `cwd` up to the filesystem root is read, the closest one winning
+/// for each key. `@scope:registry` is used, then `registry`, then the public npm registry.
+/// Returns `None` for an empty scope.
+pub fn registry_for_scope(scope: &str, options: &Options) -> Option<String> {
+    let scope = scope.trim_start_matches('@');
+
+    if scope.is_empty() {
+        return None;
+    }
+
+    let config = read_npmrc(options);
+    let registry = config
+        .get(&format!("@{}:registry", scope))
+        .or_else(|| config.get("registry"))
+        .map_or(DEFAULT_REGISTRY, String::as_str);
+
+    Some(registry.to_string())
+}
+
+/// Merge the `key=value` lines of every `.npmrc` from `cwd` upwards, closest first.
+fn read_npmrc(options: &Options) -> HashMap<String, String> {
+    let mut config = HashMap::new();
+
+    for dir in search_dirs(&cwd(options)) {
+        let Ok(npmrc) = fs::read_to_string(dir.join(".npmrc")) else {
+            continue;
+        };
+
+        for line in npmrc.lines().map(str::trim) {
+            if line.starts_with('#') || line.starts_with(';') {
+                continue;
+            }
+
+            if let Some((key, value)) = line.split_once('=') {
+                config
+                    .entry(key.trim().to_string())
+                    .or_insert_with(|| value.trim().trim_matches('"').to_string());
+            }
+        }
+    }
+
+    config
+}
+
+/// A package document from the registry's abbreviated metadata endpoint
+/// (`Accept: application/vnd.npm.install-v1+json`)
+///
+/// Registry-only fields of each version, such as `_npmUser`, are tolerated; `dist` is
+/// parsed into [`PackageJSON::dist`].
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+pub struct AbbreviatedMetadata {
+    pub name: String,
+    pub modified: Option<String>,
+    #[serde(rename = "dist-tags", default)]
+    pub dist_tags: HashMap<String, String>,
+    #[serde(default)]
+    pub versions: HashMap<String, PackageJSON>,
+}
+
+/// The `dist` field the registry adds to each published version
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+pub struct Dist {
+    /// Subresource Integrity string of the tarball, e.g. `sha512-...`.
+    pub integrity: Option<String>,
+    /// SHA-1 of the tarball, in hex.
+    pub shasum: Option<String>,
+    pub tarball: Option<String>,
+}
+
+impl AbbreviatedMetadata {
+    /// Parse an abbreviated metadata document.
+    pub fn from_json(json: &str) -> serde_json::Result<AbbreviatedMetadata> {
+        serde_json::from_str(json)
+    }
+
+    /// Get the manifest of the version a dist-tag such as `latest` points at.
+    pub fn tagged(&self, tag: &str) -> Option<&PackageJSON> {
+        self.versions.get(self.dist_tags.get(tag)?)
+    }
+}
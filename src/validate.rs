@@ -0,0 +1,118 @@
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{exports, join_manifest_path, ExportValue, PackageJSON};
+
+/// A problem found by a `check_*` lint of a manifest
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct ValidationWarning {
+    /// The `exports` subpath the problem was found under.
+    pub subpath: String,
+    pub message: String,
+}
+
+impl PackageJSON {
+    /// Check that each `types` target in `exports` has the module format of its sibling
+    /// runtime targets
+    ///
+    /// `.d.mts` pairs with `.mjs` and `.d.cts` with `.cjs`, while `.d.ts` and `.js` follow
+    /// the package `type`, as TypeScript's node16 resolution expects. `types` targets that
+    /// do not exist under `root` are reported too, except patterns such as
+    /// `./dist/*.d.ts`, which name no single file.
+    pub fn check_types_extension_pairing(&self, root: &Path) -> Vec<ValidationWarning> {
+        let Some(exports) = self.exports.as_ref() else {
+            return vec![];
+        };
+        let mut warnings = vec![];
+
+        for subpath in exports::subpaths(exports) {
+            if let Some((value, _)) = exports::get_export(exports, &subpath) {
+                self.check_pairing(value, &subpath, root, &mut warnings);
+            }
+        }
+
+        warnings
+    }
+
+    fn check_pairing(
+        &self,
+        value: &ExportValue,
+        subpath: &str,
+        root: &Path,
+        warnings: &mut Vec<ValidationWarning>,
+    ) {
+        let map = match value {
+            ExportValue::HashMap(map) => map,
+            ExportValue::Array(values) => {
+                for value in values {
+                    self.check_pairing(value, subpath, root, warnings);
+                }
+                return;
+            }
+            _ => return,
+        };
+
+        if let Some(ExportValue::String(types)) = map.get("types") {
+            let types_is_esm = self.is_esm_file(types);
+            let mut conditions: Vec<&String> = map.keys().filter(|key| *key != "types").collect();
+
+            conditions.sort();
+
+            if !types.contains('*') && !join_manifest_path(root, types).is_file() {
+                warnings.push(ValidationWarning {
+                    subpath: subpath.to_string(),
+                    message: format!("`types` target `{}` does not exist", types),
+                });
+            }
+
+            for condition in conditions {
+                let Some(ExportValue::String(target)) = map.get(condition) else {
+                    continue;
+                };
+                let target_is_esm = self.is_esm_file(target);
+
+                if target_is_esm != types_is_esm {
+                    warnings.push(ValidationWarning {
+                        subpath: subpath.to_string(),
+                        message: format!(
+                            "`{}` target `{}` is {} but `types` target `{}` is {}",
+                            condition,
+                            target,
+                            format_name(target_is_esm),
+                            types,
+                            format_name(types_is_esm)
+                        ),
+                    });
+                }
+            }
+        }
+
+        let mut nested: Vec<(&String, &ExportValue)> = map.iter().collect();
+
+        nested.sort_by_key(|(condition, _)| *condition);
+
+        for (_, value) in nested {
+            self.check_pairing(value, subpath, root, warnings);
+        }
+    }
+
+    /// Whether a runtime or declaration file is an ES module, by extension and `type`.
+    fn is_esm_file(&self, path: &str) -> bool {
+        if path.ends_with(".mjs") || path.ends_with(".d.mts") || path.ends_with(".mts") {
+            true
+        } else if path.ends_with(".cjs") || path.ends_with(".d.cts") || path.ends_with(".cts") {
+            false
+        } else {
+            self.is_module()
+        }
+    }
+}
+
+fn format_name(is_esm: bool) -> &'static str {
+    if is_esm {
+        "an ES module"
+    } else {
+        "CommonJS"
+    }
+}
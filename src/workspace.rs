@@ -0,0 +1,278 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Component, Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    find_nearest_package_json, get_package_json, range::satisfies, resolve,
+    try_load_package_info_with, CycleError, Options, PackageInfo, Required,
+};
+
+/// The `workspaces` field of a root `package.json`
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(untagged)]
+pub enum Workspaces {
+    /// `"workspaces": ["packages/*"]`
+    Array(Vec<String>),
+    /// Yarn's `"workspaces": { "packages": [...], "nohoist": [...] }`
+    Object {
+        packages: Vec<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        nohoist: Option<Vec<String>>,
+    },
+}
+
+impl Workspaces {
+    /// The workspace glob patterns, regardless of the declared form.
+    pub fn patterns(&self) -> &[String] {
+        match self {
+            Workspaces::Array(patterns) => patterns,
+            Workspaces::Object { packages, .. } => packages,
+        }
+    }
+}
+
+/// Get the workspace packages declared by the `package.json` in `cwd`
+///
+/// Patterns are expanded relative to `cwd`, `!`-prefixed patterns exclude matches,
+/// and directories inside `node_modules` are never considered workspace packages. Members
+/// without a runtime entry, such as apps, have an empty [`PackageInfo::package_entry`].
+pub fn get_workspace_packages(options: &Options) -> Vec<PackageInfo> {
+    workspace_package_dirs(options)
+        .into_iter()
+        .filter_map(|dir| load_workspace_package(&dir, options))
+        .collect()
+}
+
+/// Get the workspace package that contains `file`
+///
+/// Walks up from `file` through each enclosing `package.json` and returns the first one
+/// that is a member of the workspace declared in `cwd`.
+pub fn owning_workspace_package(file: &Path, options: &Options) -> Option<PackageInfo> {
+    let members: Vec<(PathBuf, PathBuf)> = workspace_package_dirs(options)
+        .into_iter()
+        .filter_map(|dir| Some((fs::canonicalize(&dir).ok()?, dir)))
+        .collect();
+    let mut start = fs::canonicalize(file).ok()?;
+
+    while let Some(package_json_path) = find_nearest_package_json(&start) {
+        let dir = package_json_path.parent()?;
+
+        if let Some((_, member)) = members.iter().find(|(real, _)| real == dir) {
+            return load_workspace_package(member, options);
+        }
+
+        start = dir.parent()?.to_path_buf();
+    }
+
+    None
+}
+
+/// A `workspace:` dependency that the local package does not satisfy
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct WorkspaceMismatch {
+    /// The workspace package declaring the dependency.
+    pub dependent: String,
+    pub dependency: String,
+    /// The declared spec without the `workspace:` prefix, e.g. `^1.0.0`.
+    pub range: String,
+    /// The version of the local package, or `None` when no workspace package has that name.
+    pub actual: Option<String>,
+}
+
+/// Check every `workspace:` dependency of the workspace packages against the local version
+///
+/// `workspace:*`, `workspace:^` and `workspace:~` always match an existing package; specs
+/// that are not valid ranges, such as relative paths, are not checked. Mismatches are
+/// sorted by dependent, then dependency.
+pub fn check_workspace_versions(options: &Options) -> Vec<WorkspaceMismatch> {
+    let packages = get_workspace_packages(options);
+    let mut mismatches = vec![];
+
+    for pkg in &packages {
+        let pkg_json = &pkg.package_json;
+        let dependencies = [
+            &pkg_json.dependencies,
+            &pkg_json.dev_dependencies,
+            &pkg_json.peer_dependencies,
+            &pkg_json.optional_dependencies,
+        ]
+        .into_iter()
+        .flatten()
+        .flatten();
+
+        for (dependency, spec) in dependencies {
+            let Some(range) = spec.strip_prefix("workspace:") else {
+                continue;
+            };
+            let actual = packages
+                .iter()
+                .find(|local| &local.name == dependency)
+                .map(|local| local.version.clone());
+            let matches = match &actual {
+                None => false,
+                Some(_) if matches!(range, "*" | "^" | "~") => true,
+                Some(version) => satisfies(version, range).unwrap_or(true),
+            };
+
+            if !matches {
+                mismatches.push(WorkspaceMismatch {
+                    dependent: pkg.name.clone(),
+                    dependency: dependency.clone(),
+                    range: range.to_string(),
+                    actual,
+                });
+            }
+        }
+    }
+
+    mismatches.sort_by(|a, b| (&a.dependent, &a.dependency).cmp(&(&b.dependent, &b.dependency)));
+    mismatches
+}
+
+/// Get the dependency edges between workspace packages, as sorted `(dependent, dependency)`
+/// pairs
+///
+/// Every kind of dependency counts, whether declared with the `workspace:` protocol or
+/// with a plain range matching a workspace package name.
+pub fn workspace_graph(options: &Options) -> Vec<(String, String)> {
+    let packages = get_workspace_packages(options);
+    let mut edges = vec![];
+
+    for pkg in &packages {
+        for dependency in pkg.all_dependency_names() {
+            if dependency != pkg.name && packages.iter().any(|local| local.name == dependency) {
+                edges.push((pkg.name.clone(), dependency.to_string()));
+            }
+        }
+    }
+
+    edges.sort();
+    edges.dedup();
+    edges
+}
+
+/// Get the workspace packages ordered so that each comes after its workspace dependencies
+///
+/// Packages without an ordering constraint between them are sorted by name. Fails with the
+/// packages forming a cycle when there is one.
+pub fn workspace_build_order(options: &Options) -> Result<Vec<PackageInfo>, CycleError> {
+    let mut packages: Vec<PackageInfo> = get_workspace_packages(options);
+    let mut dependencies: HashMap<String, Vec<String>> = HashMap::new();
+
+    packages.sort_by(|a, b| a.name.cmp(&b.name));
+
+    for (dependent, dependency) in workspace_graph(options) {
+        dependencies.entry(dependent).or_default().push(dependency);
+    }
+
+    let mut state = HashMap::new();
+    let mut stack = vec![];
+    let mut order = vec![];
+
+    for pkg in &packages {
+        visit(&pkg.name, &dependencies, &mut state, &mut stack, &mut order)?;
+    }
+
+    Ok(order
+        .iter()
+        .filter_map(|name| packages.iter().find(|pkg| &pkg.name == name).cloned())
+        .collect())
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum VisitState {
+    Visiting,
+    Done,
+}
+
+/// Depth-first post-order walk, reporting the stack segment that closes a cycle.
+fn visit(
+    name: &str,
+    dependencies: &HashMap<String, Vec<String>>,
+    state: &mut HashMap<String, VisitState>,
+    stack: &mut Vec<String>,
+    order: &mut Vec<String>,
+) -> Result<(), CycleError> {
+    match state.get(name) {
+        Some(VisitState::Done) => return Ok(()),
+        Some(VisitState::Visiting) => {
+            let start = stack
+                .iter()
+                .position(|visiting| visiting == name)
+                .unwrap_or(0);
+
+            return Err(CycleError {
+                packages: stack[start..].to_vec(),
+            });
+        }
+        None => {}
+    }
+
+    state.insert(name.to_string(), VisitState::Visiting);
+    stack.push(name.to_string());
+
+    for dependency in dependencies.get(name).into_iter().flatten() {
+        visit(dependency, dependencies, state, stack, order)?;
+    }
+
+    stack.pop();
+    state.insert(name.to_string(), VisitState::Done);
+    order.push(name.to_string());
+    Ok(())
+}
+
+pub(crate) fn workspace_package_dirs(options: &Options) -> Vec<PathBuf> {
+    let Ok(root_package_json) = resolve("package.json", options) else {
+        return vec![];
+    };
+    let Some(workspaces) = get_package_json(&root_package_json).and_then(|pkg| pkg.workspaces)
+    else {
+        return vec![];
+    };
+    let root = root_package_json.parent().unwrap();
+    let mut dirs = vec![];
+    let mut excluded = vec![];
+
+    for pattern in workspaces.patterns() {
+        let (pattern, negated) = match pattern.strip_prefix('!') {
+            Some(pattern) => (pattern, true),
+            None => (pattern.as_str(), false),
+        };
+        let pattern = root.join(pattern.trim_start_matches("./"));
+        let Ok(paths) = glob::glob(&pattern.to_string_lossy()) else {
+            continue;
+        };
+
+        for dir in paths.flatten() {
+            if !dir.join("package.json").is_file() || is_in_node_modules(&dir) {
+                continue;
+            }
+
+            if negated {
+                excluded.push(dir);
+            } else if !dirs.contains(&dir) {
+                dirs.push(dir);
+            }
+        }
+    }
+
+    dirs.retain(|dir| !excluded.contains(dir));
+    dirs.sort();
+    dirs
+}
+
+fn load_workspace_package(dir: &Path, options: &Options) -> Option<PackageInfo> {
+    let package_json_path = dir.join("package.json");
+    let name = get_package_json(&package_json_path)?.name?;
+
+    try_load_package_info_with(&name, package_json_path, options, Required::Version).ok()
+}
+
+fn is_in_node_modules(path: &Path) -> bool {
+    path.components()
+        .any(|component| component == Component::Normal("node_modules".as_ref()))
+}
@@ -1,6 +1,25 @@
-use std::{env::current_dir, vec};
+use std::{collections::{HashMap, HashSet}, env::current_dir, fs, path::{Path, PathBuf}, vec};
 
-use npm_pkg::{get_package_info, is_package_exists, Options, PackageInfo, PackageJSON};
+use npm_pkg::{
+    check_workspace_versions, dependency_depths, dependency_location, diff_installed,
+    duplicate_version_report, engine_satisfies, explain_resolution, find_installed,
+    get_package_export, get_package_info, get_package_json_path, get_package_subpath, get_package_version,
+    get_partial_package_info, get_self_info, get_types_entry, get_workspace_packages,
+    installed_versions, invalidate_scan_cache, is_package_exists, is_resolvable_but_undeclared,
+    list_installed_packages, node_modules_roots, owning_workspace_package, package_satisfies,
+    reachable_dependencies, registry_for_scope, required_conditions_for, resolve_all_locations,
+    resolve_import, resolve_optional, resolve_via_pnpm_store, scan_with_errors,
+    try_get_package_export, try_get_package_info, unreachable_dependencies,
+    verify_dependencies_installed, workspace_build_order, workspace_graph, AbbreviatedMetadata,
+    Anchor, BinType, Browser, BrowserValue, Bugs, CycleError, DependencyLocation, ExportValue,
+    Funding, InstalledQuery, License, ModuleKind, ModuleType, OptionalResolution, Options,
+    PackageCache, PackageError, PackageIndex, PackageInfo, PackageJSON, Person, Repository,
+    ResolvedDependency, Target, ValidationWarning, VersionChange, WorkspaceMismatch, Workspaces,
+};
+
+fn fixture(cwd: &'static str) -> Options<'static> {
+    Options { cwd: Some(cwd), ..Default::default() }
+}
 
 #[test]
 fn it_should_get_package_info() {
@@ -10,24 +29,26 @@ fn it_should_get_package_info() {
         name: String::from("consola"),
         version: String::from("3.2.3"),
         root_path: current_dir().unwrap().join("node_modules/consola"),
+        real_path: current_dir().unwrap().join("node_modules/consola"),
         package_json_path: current_dir().unwrap().join("node_modules/consola/package.json"),
         package_entry: current_dir().unwrap().join("node_modules/consola/dist/index.mjs"),
+        module_kind: ModuleKind::Esm,
         package_json: PackageJSON { 
             name: Some(String::from("consola")), 
             version: Some(String::from("3.2.3")), 
             description: Some(String::from("Elegant Console Wrapper")), 
             homepage: None, 
             keywords: Some(vec![String::from("console"), String::from("logger"), String::from("reporter"), String::from("elegant"), String::from("cli"), String::from("universal"), String::from("unified"), String::from("prompt"), String::from("clack"), String::from("format"), String::from("error"), String::from("stacktrace")]), 
-            license: Some(String::from("MIT")), 
+            license: Some(License::String(String::from("MIT"))), 
             private: None, 
             author: None, 
             files: Some(vec![String::from("dist"), String::from("lib"), String::from("*.d.ts")]), 
             r#type: Some(String::from("module")), 
             main: Some(String::from("./lib/index.cjs")), 
             module: Some(String::from("./dist/index.mjs")), 
-            exports: None, 
+            exports: Some(serde_json::from_str(r#"{ ".": { "node": { "types": "./dist/index.d.ts", "import": "./dist/index.mjs", "require": "./lib/index.cjs" }, "default": { "types": "./dist/browser.d.ts", "import": "./dist/browser.mjs" } }, "./browser": { "types": "./dist/browser.d.ts", "import": "./dist/browser.mjs" }, "./basic": { "node": { "types": "./dist/basic.d.ts", "import": "./dist/basic.mjs", "require": "./dist/basic.cjs" }, "default": { "types": "./dist/browser.d.ts", "import": "./dist/browser.mjs" } }, "./core": { "types": "./dist/core.d.ts", "import": "./dist/core.mjs", "require": "./dist/core.cjs" }, "./utils": { "types": "./dist/utils.d.ts", "import": "./dist/utils.mjs", "require": "./dist/utils.cjs" } }"#).unwrap()), 
             types: Some(String::from("./dist/index.d.ts")), 
-            browser: Some(String::from("./dist/browser.mjs")), 
+            browser: Some(Browser::String(String::from("./dist/browser.mjs"))), 
             bin: None, 
             scripts: Some(serde_json::from_str(r#"{ "build": "unbuild", "lint:fix": "eslint . --fix && prettier -w src examples test", "lint": "eslint . && prettier -c src examples test", "test": "pnpm lint && pnpm vitest run --coverage", "release": "pnpm test && pnpm build && changelogen --release --push && npm publish", "dev": "vitest" }"#).unwrap()), 
             dependencies: None, 
@@ -35,8 +56,21 @@ fn it_should_get_package_info() {
             peer_dependencies: None, 
             peer_dependencies_meta: None, 
             optional_dependencies: None, 
-            engines: Some(serde_json::from_str(r#"{"node": "^14.18.0 || >=16.10.0"}"#).unwrap()) 
-        }
+            engines: Some(serde_json::from_str(r#"{"node": "^14.18.0 || >=16.10.0"}"#).unwrap()),
+            workspaces: None,
+            publish_config: None,
+            overrides: None,
+            directories: None,
+            extra: serde_json::from_str(r#"{ "packageManager": "pnpm@8.6.5" }"#).unwrap(),
+            dist: None,
+            react_native: None,
+            imports: None,
+            repository: Some(Repository::String(String::from("unjs/consola"))),
+            bugs: None,
+            contributors: None,
+            funding: None,
+        },
+        raw_json: None
     }));
 }
 
@@ -44,4 +78,1617 @@ fn it_should_get_package_info() {
 fn it_should_package_exists() {
     assert!(is_package_exists("magic-string", &Options::default()));
     assert!(!is_package_exists("abc", &Options::default()));
-}
\ No newline at end of file
+}
+#[test]
+fn it_should_diff_installed() {
+    let diff = diff_installed(
+        &fixture("tests/fixtures/diff-a"),
+        &fixture("tests/fixtures/diff-b"),
+    );
+
+    let added: Vec<&str> = diff.added.iter().map(|pkg| pkg.name.as_str()).collect();
+    let removed: Vec<&str> = diff.removed.iter().map(|pkg| pkg.name.as_str()).collect();
+
+    assert_eq!(added, vec!["@scope/added"]);
+    assert_eq!(removed, vec!["removed"]);
+    assert_eq!(diff.changed, vec![VersionChange {
+        name: String::from("left-pad"),
+        from: String::from("1.0.0"),
+        to: String::from("1.3.0"),
+    }]);
+}
+
+#[test]
+fn it_should_probe_index_by_module_type() {
+    let esm = get_package_info("module-index", fixture("tests/fixtures/module-type")).unwrap();
+    let cjs = get_package_info("commonjs-index", fixture("tests/fixtures/module-type")).unwrap();
+
+    assert_eq!(esm.package_entry, esm.root_path.join("index.mjs"));
+    assert_eq!(cjs.package_entry, cjs.root_path.join("index.cjs"));
+}
+
+#[test]
+fn it_should_get_workspace_packages() {
+    let names: Vec<String> = get_workspace_packages(&fixture("tests/fixtures/workspace"))
+        .into_iter()
+        .map(|pkg| pkg.name)
+        .collect();
+
+    assert_eq!(names, vec!["app", "utils"]);
+}
+
+#[test]
+fn it_should_parse_both_workspaces_forms() {
+    let array: PackageJSON = serde_json::from_str(r#"{ "workspaces": ["packages/*"] }"#).unwrap();
+    let object: PackageJSON = serde_json::from_str(r#"{ "workspaces": { "packages": ["packages/*", "tools/*"], "nohoist": ["**/react-native"] } }"#).unwrap();
+
+    assert_eq!(array.workspaces, Some(Workspaces::Array(vec![String::from("packages/*")])));
+    assert_eq!(object.workspaces.as_ref().unwrap().patterns(), ["packages/*", "tools/*"]);
+    assert!(matches!(&object.workspaces, Some(Workspaces::Object { nohoist: Some(nohoist), .. }) if nohoist == &["**/react-native"]));
+    assert_eq!(serde_json::to_value(&object).unwrap()["workspaces"], serde_json::json!({ "packages": ["packages/*", "tools/*"], "nohoist": ["**/react-native"] }));
+}
+
+#[test]
+fn it_should_find_owning_workspace_package() {
+    let options = fixture("tests/fixtures/workspace");
+    let owner = owning_workspace_package(Path::new("tests/fixtures/workspace/packages/app/src/main.js"), &options);
+
+    assert_eq!(owner.map(|pkg| pkg.name), Some(String::from("app")));
+    assert_eq!(owning_workspace_package(Path::new("tests/fixtures/workspace/node_modules/dep/index.js"), &options), None);
+}
+
+#[test]
+fn it_should_get_self_info() {
+    let self_info = get_self_info(&fixture("tests/fixtures/self-info")).unwrap();
+
+    assert_eq!(self_info.name, "self-info");
+    assert_eq!(self_info.version, "2.1.0");
+    assert_eq!(self_info.root_path, Path::new("tests/fixtures/self-info"));
+    assert_eq!(get_self_info(&fixture("tests/fixtures/diff-a")), None);
+
+    let monorepo = get_self_info(&fixture("tests/fixtures/monorepo")).unwrap();
+
+    assert_eq!(monorepo.name, "monorepo");
+    assert_eq!(monorepo.version, "");
+    assert_eq!(monorepo.package_entry, PathBuf::new());
+    assert_eq!(monorepo.package_json.private, Some(true));
+    assert_eq!(monorepo.package_json.workspaces, Some(Workspaces::Array(vec![String::from("packages/*")])));
+}
+
+#[test]
+fn it_should_scan_with_errors() {
+    let (packages, errors) = scan_with_errors(&fixture("tests/fixtures/scan-errors"));
+    let names: Vec<&str> = packages.iter().map(|pkg| pkg.name.as_str()).collect();
+
+    assert_eq!(names, vec!["good"]);
+    assert_eq!(errors.len(), 2);
+    assert_eq!(errors[0].0, Path::new("tests/fixtures/scan-errors/node_modules/broken/package.json"));
+    assert!(matches!(errors[0].1, PackageError::ParseFailed { .. }));
+    assert_eq!(errors[1].0, Path::new("tests/fixtures/scan-errors/node_modules/unversioned/package.json"));
+    assert!(matches!(errors[1].1, PackageError::MissingVersion { .. }));
+}
+
+#[test]
+fn it_should_resolve_asset() {
+    let pkg_info = get_package_info("magic-string", Options::default()).unwrap();
+
+    assert_eq!(
+        pkg_info.resolve_asset("dist/magic-string.cjs.js"),
+        Some(current_dir().unwrap().join("node_modules/magic-string/dist/magic-string.cjs.js").canonicalize().unwrap())
+    );
+    assert_eq!(pkg_info.resolve_asset("dist/missing.wasm"), None);
+    assert_eq!(pkg_info.resolve_asset("../consola/package.json"), None);
+    assert_eq!(pkg_info.resolve_asset("../../Cargo.toml"), None);
+}
+
+#[test]
+fn it_should_get_all_dependency_names() {
+    let pkg_info = get_package_info("magic-string", Options::default()).unwrap();
+    let names = pkg_info.all_dependency_names();
+
+    assert_eq!(names.len(), 13);
+    assert!(names.contains("@jridgewell/sourcemap-codec"));
+    assert!(names.contains("rollup"));
+    assert!(!names.contains("magic-string"));
+
+    let pkg_info = get_package_info("left-pad", fixture("tests/fixtures/diff-a")).unwrap();
+
+    assert!(pkg_info.all_dependency_names().is_empty());
+}
+
+#[test]
+fn it_should_resolve_entry_and_types_from_combined_exports() {
+    let pkg_info = get_package_info("combined-exports", fixture("tests/fixtures/exports")).unwrap();
+
+    assert_eq!(pkg_info.entry_for(Target::Node, &[]), Some(pkg_info.root_path.join("index.mjs")));
+    assert_eq!(pkg_info.entry_for(Target::Browser, &[]), Some(pkg_info.root_path.join("index.mjs")));
+    assert_eq!(pkg_info.types_entry(), Some(pkg_info.root_path.join("index.d.ts")));
+
+    let consola = get_package_info("consola", Options::default()).unwrap();
+
+    assert_eq!(consola.entry_for(Target::Node, &[]), Some(consola.root_path.join("dist/index.mjs")));
+    assert_eq!(consola.entry_for(Target::Browser, &[]), Some(consola.root_path.join("dist/browser.mjs")));
+    assert_eq!(consola.types_entry(), Some(consola.root_path.join("dist/index.d.ts")));
+}
+
+#[test]
+fn it_should_report_real_path_of_symlinked_package() {
+    let options = Options { follow_symlinks: true, ..fixture("tests/fixtures/symlinks") };
+    let pkg_info = get_package_info("linked", options).unwrap();
+
+    assert_eq!(pkg_info.root_path, Path::new("tests/fixtures/symlinks/node_modules/linked"));
+    assert_eq!(pkg_info.real_path, Path::new("tests/fixtures/symlinks/store/linked").canonicalize().unwrap());
+
+    let pkg_info = get_package_info("linked", fixture("tests/fixtures/symlinks")).unwrap();
+
+    assert_eq!(pkg_info.real_path, pkg_info.root_path);
+
+    let options = Options { follow_symlinks: true, ..Default::default() };
+    let pkg_info = get_package_info("magic-string", options).unwrap();
+
+    assert_eq!(pkg_info.real_path, pkg_info.root_path);
+}
+
+#[test]
+fn it_should_report_exports_usage() {
+    assert!(get_package_info("consola", Options::default()).unwrap().uses_exports());
+    assert!(!get_package_info("left-pad", fixture("tests/fixtures/diff-a")).unwrap().uses_exports());
+}
+
+#[test]
+fn it_should_resolve_entry_for_deno() {
+    let pkg_info = get_package_info("deno-export", fixture("tests/fixtures/exports")).unwrap();
+
+    assert_eq!(pkg_info.entry_for(Target::Deno, &[]), Some(pkg_info.root_path.join("deno.js")));
+    assert_eq!(pkg_info.entry_for(Target::Node, &[]), Some(pkg_info.root_path.join("node.js")));
+    assert_eq!(pkg_info.entry_for(Target::Browser, &[]), Some(pkg_info.root_path.join("index.js")));
+
+    let pkg_info = get_package_info("browser-field", fixture("tests/fixtures/exports")).unwrap();
+
+    assert_eq!(pkg_info.entry_for(Target::Deno, &[]), Some(pkg_info.root_path.join("index.js")));
+    assert_eq!(pkg_info.entry_for(Target::Browser, &[]), Some(pkg_info.root_path.join("browser.js")));
+}
+
+#[test]
+fn it_should_find_installed() {
+    let names = |query: InstalledQuery| -> Vec<String> {
+        let mut names: Vec<String> = find_installed(&query, &Options::default()).into_iter().map(|pkg| pkg.name).collect();
+        names.sort();
+        names
+    };
+
+    assert_eq!(names(InstalledQuery { name: Some(String::from("@jridgewell/*")), ..Default::default() }), vec!["@jridgewell/sourcemap-codec"]);
+    assert_eq!(names(InstalledQuery { keyword: Some(String::from("sourcemap")), ..Default::default() }), vec!["@jridgewell/sourcemap-codec", "magic-string"]);
+    assert_eq!(names(InstalledQuery { license: Some(String::from("MIT")), name: Some(String::from("c*")), ..Default::default() }), vec!["consola"]);
+    assert!(names(InstalledQuery { license: Some(String::from("GPL-*")), ..Default::default() }).is_empty());
+}
+
+#[test]
+fn it_should_not_fall_back_to_main_with_empty_exports() {
+    let options = fixture("tests/fixtures/exports");
+
+    assert!(is_package_exists("empty-exports", &options));
+    assert_eq!(get_package_info("empty-exports", options), None);
+}
+
+#[test]
+fn it_should_get_module_type() {
+    let esm = get_package_info("module-index", fixture("tests/fixtures/module-type")).unwrap();
+    let cjs = get_package_info("commonjs-index", fixture("tests/fixtures/module-type")).unwrap();
+    let untyped = get_package_info("left-pad", fixture("tests/fixtures/diff-a")).unwrap();
+
+    assert_eq!(esm.module_type(), ModuleType::Module);
+    assert_eq!(cjs.module_type(), ModuleType::CommonJs);
+    assert_eq!(untyped.module_type(), ModuleType::Unspecified);
+    assert!(esm.is_esm() && !esm.is_cjs());
+    assert!(cjs.is_cjs() && !cjs.is_esm());
+    assert!(untyped.is_cjs());
+}
+
+#[test]
+fn it_should_probe_extensionless_main() {
+    let bare = get_package_info("bare-index", fixture("tests/fixtures/main-field")).unwrap();
+    let directory = get_package_info("main-directory", fixture("tests/fixtures/main-field")).unwrap();
+
+    assert_eq!(bare.package_entry, bare.root_path.join("index.js"));
+    assert_eq!(bare.entry_for(Target::Node, &[]), Some(bare.root_path.join("index.js")));
+    assert_eq!(directory.package_entry, directory.root_path.join("lib/index.js"));
+}
+
+#[test]
+fn it_should_parse_abbreviated_metadata() {
+    let json = fs::read_to_string("tests/fixtures/registry/left-pad.json").unwrap();
+    let metadata = AbbreviatedMetadata::from_json(&json).unwrap();
+
+    assert_eq!(metadata.name, "left-pad");
+    assert_eq!(metadata.dist_tags.get("latest"), Some(&String::from("1.3.0")));
+    assert_eq!(metadata.versions.len(), 2);
+    assert_eq!(metadata.tagged("latest").and_then(|pkg| pkg.version.clone()), Some(String::from("1.3.0")));
+    assert_eq!(metadata.versions["1.2.0"].dev_dependencies.as_ref().unwrap()["benchmark"], "^2.1.0");
+    assert_eq!(metadata.tagged("next"), None);
+}
+
+#[test]
+fn it_should_get_bin_path() {
+    let map = get_package_info("bin-map", fixture("tests/fixtures/bin")).unwrap();
+    let string = get_package_info("@scope/bin-string", fixture("tests/fixtures/bin")).unwrap();
+
+    assert_eq!(map.bin_path("map-a"), Some(map.root_path.join("bin/a.js")));
+    assert_eq!(map.bin_path("map-c"), None);
+    assert_eq!(string.bin_path("bin-string"), Some(string.root_path.join("cli.js")));
+    assert_eq!(string.bin_path("other"), None);
+    assert_eq!(get_package_info("consola", Options::default()).unwrap().bin_path("consola"), None);
+}
+
+#[test]
+fn it_should_respect_allowed_and_denied_packages() {
+    let names = |names: &[&str]| Some(names.iter().map(|name| name.to_string()).collect::<HashSet<String>>());
+    let allowed = Options { allowed_packages: names(&["consola", "magic-string"]), ..Default::default() };
+
+    assert!(is_package_exists("consola", &allowed));
+    assert!(!is_package_exists("@jridgewell/sourcemap-codec", &allowed));
+
+    let denied = Options { denied_packages: names(&["consola"]), ..Default::default() };
+
+    assert_eq!(get_package_info("consola", denied), None);
+
+    let both = Options { allowed_packages: names(&["consola", "magic-string"]), denied_packages: names(&["consola"]), ..Default::default() };
+
+    assert!(!is_package_exists("consola", &both));
+    assert!(is_package_exists("magic-string", &both));
+}
+
+#[test]
+fn it_should_apply_publish_config() {
+    let pkg_json: PackageJSON = serde_json::from_str(&fs::read_to_string("tests/fixtures/publish/package.json").unwrap()).unwrap();
+    let published = pkg_json.to_published();
+
+    assert_eq!(published.main, Some(String::from("./dist/index.js")));
+    assert_eq!(published.types, Some(String::from("./dist/index.d.ts")));
+    assert_eq!(published.name, pkg_json.name);
+    assert_eq!(published.publish_config, Some(serde_json::from_str(r#"{ "access": "public" }"#).unwrap()));
+    assert_eq!(pkg_json.main, Some(String::from("./src/index.ts")));
+}
+
+#[test]
+fn it_should_resolve_nested_exports() {
+    let exports = |json: &str| -> ExportValue { serde_json::from_str(json).unwrap() };
+
+    let sugar = exports(r#""./index.js""#);
+    assert_eq!(sugar.resolve(".", &["default"]).as_deref(), Some("./index.js"));
+    assert_eq!(sugar.resolve("./index.js", &["default"]), None);
+
+    let conditions = exports(r#"{ "import": "./index-module.js", "require": "./index-require.cjs" }"#);
+    assert_eq!(conditions.resolve(".", &["require"]).as_deref(), Some("./index-require.cjs"));
+    assert_eq!(conditions.resolve(".", &["import", "require"]).as_deref(), Some("./index-module.js"));
+    assert_eq!(conditions.resolve(".", &["require", "import"]).as_deref(), Some("./index-module.js"));
+    assert_eq!(exports(r#"{ "default": "./d.js", "import": "./i.mjs" }"#).resolve(".", &["import", "default"]).as_deref(), Some("./d.js"));
+
+    let nested = exports(r#"{ "node": { "import": "./feature-node.mjs", "require": "./feature-node.cjs" }, "default": "./feature.mjs" }"#);
+    assert_eq!(nested.resolve(".", &["node", "require", "default"]).as_deref(), Some("./feature-node.cjs"));
+    assert_eq!(nested.resolve(".", &["browser", "import", "default"]).as_deref(), Some("./feature.mjs"));
+    assert_eq!(nested.resolve(".", &["node", "default"]).as_deref(), Some("./feature.mjs"));
+    assert_eq!(nested.resolve(".", &["browser"]).as_deref(), Some("./feature.mjs"));
+    assert_eq!(exports(r#"{ ".": { "browser": "./b.js", "default": "./d.js" } }"#).resolve(".", &["node"]).as_deref(), Some("./d.js"));
+
+    let subpaths = exports(r#"{ ".": "./index.js", "./feature.js": { "node": "./feature-node.js", "default": "./feature.js" }, "./internal": null }"#);
+    assert_eq!(subpaths.resolve(".", &["node"]).as_deref(), Some("./index.js"));
+    assert_eq!(subpaths.resolve("./feature.js", &["node", "default"]).as_deref(), Some("./feature-node.js"));
+    assert_eq!(subpaths.resolve("./feature.js", &["default"]).as_deref(), Some("./feature.js"));
+    assert_eq!(subpaths.resolve("./internal", &["default"]), None);
+
+    let fallbacks = exports(r#"{ ".": [{ "worker": "./worker.js" }, "./index.js"] }"#);
+    assert_eq!(fallbacks.resolve(".", &["worker"]).as_deref(), Some("./worker.js"));
+    assert_eq!(fallbacks.resolve(".", &["node"]).as_deref(), Some("./index.js"));
+
+    let invalid = exports(r#"{ ".": "./index.js", "default": "./other.js" }"#);
+    assert_eq!(invalid.resolve(".", &["default"]), None);
+    assert_eq!(exports(r#"{ ".": "../outside.js" }"#).resolve(".", &["default"]), None);
+}
+
+#[test]
+fn it_should_report_entries_not_covered_by_files() {
+    let root = Path::new("tests/fixtures/files");
+    let pkg_json: PackageJSON = serde_json::from_str(&fs::read_to_string(root.join("package.json")).unwrap()).unwrap();
+
+    assert_eq!(pkg_json.entry_covered_by_files(root), vec!["./esm/index.mjs", "./esm/*.mjs"]);
+
+    let consola = get_package_info("consola", Options::default()).unwrap();
+
+    assert!(consola.package_json.entry_covered_by_files(&consola.root_path).is_empty());
+}
+
+#[test]
+fn it_should_get_best_entry() {
+    let options = fixture("tests/fixtures/best-entry");
+    let best_entry = |name: &str| {
+        let pkg_info = get_package_info(name, fixture("tests/fixtures/best-entry")).unwrap();
+        let best_entry = pkg_info.best_entry(&options).unwrap();
+
+        best_entry.strip_prefix(&pkg_info.root_path).unwrap().to_path_buf()
+    };
+    let combined = get_package_info("combined-exports", fixture("tests/fixtures/exports")).unwrap();
+
+    assert_eq!(combined.best_entry(&options), Some(combined.root_path.join("index.mjs")));
+    assert_eq!(best_entry("exports-missing"), Path::new("esm.mjs"));
+    assert_eq!(best_entry("module-missing"), Path::new("main.js"));
+    assert_eq!(best_entry("index-only"), Path::new("index.js"));
+}
+
+#[test]
+fn it_should_get_scope() {
+    let named = |name: &str| PackageInfo { name: name.to_string(), ..Default::default() };
+
+    assert_eq!(named("@babel/core").scope(), Some("@babel"));
+    assert_eq!(named("@babel/core").unscoped_name(), "core");
+    assert_eq!(named("consola").scope(), None);
+    assert_eq!(named("consola").unscoped_name(), "consola");
+    assert_eq!(named("@scope/nested/name").scope(), Some("@scope"));
+    assert_eq!(named("@scope/nested/name").unscoped_name(), "nested/name");
+    assert_eq!(named("@scope").scope(), None);
+    assert_eq!(named("@/name").unscoped_name(), "@/name");
+}
+
+#[test]
+fn it_should_resolve_catch_all_exports_last() {
+    let pkg_info = get_package_info("catch-all", fixture("tests/fixtures/exports")).unwrap();
+    let exports = pkg_info.package_json.exports.as_ref().unwrap();
+    let resolve = |subpath: &str| exports.resolve(subpath, &["default"]);
+
+    assert_eq!(resolve(".").as_deref(), Some("./dist/index.js"));
+    assert_eq!(resolve("./package.json").as_deref(), Some("./package.json"));
+    assert_eq!(resolve("./feature.js").as_deref(), Some("./dist/feature.js"));
+    assert_eq!(resolve("./nested/deep/file.mjs").as_deref(), Some("./dist/nested/deep/file.mjs"));
+    assert_eq!(resolve("./utils/math").as_deref(), Some("./lib/utils/math.js"));
+    assert_eq!(resolve("./../secret.js"), None);
+}
+
+#[test]
+fn it_should_always_export_the_package_json() {
+    let options = fixture("tests/fixtures/exports");
+    let root = Path::new("tests/fixtures/exports/node_modules");
+
+    assert_eq!(get_package_export("directory-mount", "./index.js", &[], &options), None);
+    assert_eq!(get_package_export("directory-mount", "./package.json", &[], &options), Some(root.join("directory-mount/package.json")));
+    assert_eq!(get_package_export("empty-exports", "./package.json", &[], &options), Some(root.join("empty-exports/package.json")));
+    assert_eq!(get_package_export("blocked-manifest", "./package.json", &[], &options), None);
+}
+
+#[test]
+fn it_should_get_dependency_depths() {
+    let depths = dependency_depths("a", &fixture("tests/fixtures/graph"));
+
+    assert_eq!(depths, HashMap::from([
+        (String::from("a"), 0),
+        (String::from("b"), 1),
+        (String::from("c"), 1),
+        (String::from("d"), 2),
+        (String::from("e"), 3),
+    ]));
+    assert!(dependency_depths("missing", &fixture("tests/fixtures/graph")).is_empty());
+}
+
+#[test]
+fn it_should_check_types_extension_pairing() {
+    let pkg_info = get_package_info("types-pairing", fixture("tests/fixtures/exports")).unwrap();
+    let warnings = pkg_info.package_json.check_types_extension_pairing(&pkg_info.root_path);
+
+    assert_eq!(warnings, vec![
+        ValidationWarning {
+            subpath: String::from("."),
+            message: String::from("`import` target `./dist/index.mjs` is an ES module but `types` target `./dist/index.d.ts` is CommonJS"),
+        },
+        ValidationWarning {
+            subpath: String::from("./features/*"),
+            message: String::from("`import` target `./dist/features/*.cjs` is CommonJS but `types` target `./dist/features/*.d.mts` is an ES module"),
+        },
+        ValidationWarning {
+            subpath: String::from("./missing"),
+            message: String::from("`types` target `./dist/missing.d.cts` does not exist"),
+        },
+    ]);
+
+    let consola = get_package_info("consola", Options::default()).unwrap();
+    let subpaths: Vec<String> = consola.package_json.check_types_extension_pairing(&consola.root_path)
+        .into_iter()
+        .map(|warning| warning.subpath)
+        .collect();
+
+    assert_eq!(subpaths, vec![".", "./basic", "./core", "./utils"]);
+}
+
+#[test]
+fn it_should_get_partial_package_info() {
+    let options = fixture("tests/fixtures/partial");
+    let no_entry = get_partial_package_info("no-entry", &options).unwrap();
+
+    assert_eq!(no_entry.version, Some(String::from("1.0.0")));
+    assert_eq!(no_entry.package_entry, None);
+    assert_eq!(no_entry.package_json.unwrap().dependencies.unwrap()["left-pad"], "^1.3.0");
+    assert!(matches!(no_entry.problems[..], [PackageError::MissingEntry { .. }]));
+
+    let bad_fields = get_partial_package_info("bad-fields", &options).unwrap();
+    let mut invalid: Vec<&str> = bad_fields.problems.iter().filter_map(|problem| match problem {
+        PackageError::InvalidField { field, .. } => Some(field.as_str()),
+        _ => None,
+    }).collect();
+    invalid.sort();
+
+    assert_eq!(invalid, vec!["keywords", "license"]);
+    assert!(bad_fields.problems.iter().any(|problem| matches!(problem, PackageError::MissingVersion { .. })));
+    assert_eq!(bad_fields.package_entry, Some(bad_fields.root_path.join("index.js")));
+    assert_eq!(bad_fields.package_json.unwrap().name, Some(String::from("bad-fields")));
+    assert!(get_partial_package_info("missing", &options).is_none());
+}
+
+#[test]
+fn it_should_list_all_export_targets() {
+    let pkg_info = get_package_info("@jridgewell/sourcemap-codec", Options::default()).unwrap();
+    let root = &pkg_info.root_path;
+    let target = |subpath: &str, condition: &str, path: &str| (subpath.to_string(), condition.to_string(), root.join(path));
+
+    assert_eq!(pkg_info.all_export_targets(), vec![
+        target(".", "browser", "./dist/sourcemap-codec.umd.js"),
+        target(".", "default", "./dist/sourcemap-codec.umd.js"),
+        target(".", "import", "./dist/sourcemap-codec.mjs"),
+        target(".", "require", "./dist/sourcemap-codec.umd.js"),
+        target(".", "types", "./dist/types/sourcemap-codec.d.ts"),
+        target("./package.json", "default", "./package.json"),
+    ]);
+
+    let consola = get_package_info("consola", Options::default()).unwrap();
+    let targets = consola.all_export_targets();
+
+    assert_eq!(targets.len(), 18);
+    assert!(targets.contains(&(String::from("."), String::from("node.require"), consola.root_path.join("./lib/index.cjs"))));
+    assert!(targets.iter().all(|(_, _, path)| path.is_file()));
+}
+
+#[test]
+fn it_should_apply_overrides() {
+    let pkg_json: PackageJSON = serde_json::from_str(r#"{
+        "dependencies": { "qux": "^4.0.0" },
+        "overrides": {
+            "foo": "1.0.0",
+            "bar": { ".": "2.0.0", "baz": "3.0.0", "foo": "1.5.0" },
+            "qux": "$qux"
+        }
+    }"#).unwrap();
+
+    assert_eq!(pkg_json.apply_overrides(&[], "foo", "^0.1.0"), "1.0.0");
+    assert_eq!(pkg_json.apply_overrides(&[], "bar", "^1.0.0"), "2.0.0");
+    assert_eq!(pkg_json.apply_overrides(&["other"], "bar", "^1.0.0"), "2.0.0");
+    assert_eq!(pkg_json.apply_overrides(&["bar"], "baz", "^1.0.0"), "3.0.0");
+    assert_eq!(pkg_json.apply_overrides(&["bar", "nested"], "baz", "^1.0.0"), "3.0.0");
+    assert_eq!(pkg_json.apply_overrides(&["bar"], "foo", "^0.1.0"), "1.5.0");
+    assert_eq!(pkg_json.apply_overrides(&["other"], "baz", "^1.0.0"), "^1.0.0");
+    assert_eq!(pkg_json.apply_overrides(&["bar"], ".", "^1.0.0"), "^1.0.0");
+    assert_eq!(pkg_json.apply_overrides(&[], "qux", "^3.0.0"), "^4.0.0");
+}
+
+#[test]
+fn it_should_list_node_modules_roots() {
+    let roots = node_modules_roots(&fixture("tests/fixtures/graph/node_modules/b"));
+
+    assert_eq!(
+        roots,
+        vec![
+            Path::new("tests/fixtures/graph/node_modules/b/node_modules"),
+            Path::new("tests/fixtures/graph/node_modules"),
+            Path::new("node_modules"),
+        ]
+    );
+    assert_eq!(node_modules_roots(&fixture("tests/fixtures/../fixtures/graph/node_modules/b")), roots);
+
+    let outside = node_modules_roots(&fixture("../outside-the-crate"));
+
+    assert!(!outside.contains(&Path::new("node_modules").to_path_buf()));
+    assert!(!outside.contains(&current_dir().unwrap().join("node_modules")));
+}
+
+#[test]
+fn it_should_coerce_a_numeric_version() {
+    let pkg_info = get_package_info("numeric-version", fixture("tests/fixtures/partial")).unwrap();
+
+    assert_eq!(pkg_info.version, "1");
+
+    let partial = get_partial_package_info("numeric-version", &fixture("tests/fixtures/partial")).unwrap();
+
+    assert!(partial.problems.is_empty());
+    assert_eq!(partial.version, Some(String::from("1")));
+    assert_eq!(partial.warnings, vec!["`version` is not a string, read 1 as \"1\""]);
+}
+
+#[test]
+fn it_should_check_workspace_versions() {
+    let mismatches = check_workspace_versions(&fixture("tests/fixtures/workspace-versions"));
+
+    assert_eq!(mismatches, vec![
+        WorkspaceMismatch {
+            dependent: String::from("app"),
+            dependency: String::from("core"),
+            range: String::from("^2.0.0"),
+            actual: Some(String::from("1.5.0")),
+        },
+        WorkspaceMismatch {
+            dependent: String::from("cli"),
+            dependency: String::from("gone"),
+            range: String::from("~1.0.0"),
+            actual: None,
+        },
+    ]);
+    assert!(check_workspace_versions(&fixture("tests/fixtures/workspace")).is_empty());
+}
+
+#[test]
+fn it_should_use_posix_paths() {
+    let native = get_package_info("consola", Options::default()).unwrap();
+    let posix = get_package_info("consola", Options { posix_paths: true, ..Default::default() }).unwrap();
+
+    for path in [&posix.root_path, &posix.real_path, &posix.package_json_path, &posix.package_entry] {
+        assert!(!path.to_string_lossy().contains('\\'));
+    }
+
+    assert_eq!(posix.package_entry.to_string_lossy(), native.package_entry.to_string_lossy().replace(std::path::MAIN_SEPARATOR, "/"));
+}
+
+#[test]
+fn it_should_find_the_changelog() {
+    let with_changelog = get_package_info("with-changelog", fixture("tests/fixtures/changelog")).unwrap();
+    let with_history = get_package_info("with-history", fixture("tests/fixtures/changelog")).unwrap();
+    let consola = get_package_info("consola", Options::default()).unwrap();
+
+    assert_eq!(with_changelog.changelog_path(), Some(with_changelog.root_path.join("Changelog.md")));
+    assert_eq!(with_history.changelog_path(), Some(with_history.root_path.join("HISTORY.md")));
+    assert_eq!(consola.changelog_path(), None);
+}
+
+#[test]
+fn it_should_override_conditions_per_call() {
+    let options = Options::default();
+    let consola = get_package_info("consola", Options::default()).unwrap();
+    let root = &consola.root_path;
+
+    assert_eq!(consola.entry_for(Target::Node, &["node", "require"]), Some(root.join("lib/index.cjs")));
+    assert_eq!(consola.entry_for(Target::Node, &["default", "import"]), Some(root.join("dist/browser.mjs")));
+    assert_eq!(get_package_export("consola", "./core", &["require"], &options), Some(root.join("dist/core.cjs")));
+    assert_eq!(get_package_export("consola", "./core", &[], &options), Some(root.join("dist/core.mjs")));
+    assert_eq!(get_package_export("consola", "./missing", &[], &options), None);
+}
+
+#[test]
+fn it_should_tell_nested_from_hoisted_dependencies() {
+    let options = fixture("tests/fixtures/graph");
+    let b = get_package_info("b", fixture("tests/fixtures/graph")).unwrap();
+
+    assert_eq!(dependency_location(&b, "d", &options), Some(DependencyLocation::Nested(b.root_path.join("node_modules/d"))));
+    assert_eq!(dependency_location(&b, "c", &options), Some(DependencyLocation::Hoisted(Path::new("tests/fixtures/graph/node_modules/c").to_path_buf())));
+    assert_eq!(dependency_location(&b, "missing", &options), None);
+}
+
+#[test]
+fn it_should_collect_installed_versions() {
+    let versions = installed_versions(&fixture("tests/fixtures/graph"));
+
+    assert_eq!(versions["d"], vec!["1.0.0", "2.0.0"]);
+    assert_eq!(versions["a"], vec!["1.0.0"]);
+    assert_eq!(versions.len(), 7);
+}
+
+#[test]
+fn it_should_report_duplicate_versions() {
+    let report = duplicate_version_report(&fixture("tests/fixtures/graph"));
+    let size = |dir: &str| ["index.js", "package.json"].iter().map(|file| fs::metadata(Path::new(dir).join(file)).unwrap().len()).sum::<u64>();
+    let hoisted = size("tests/fixtures/graph/node_modules/d");
+    let nested = size("tests/fixtures/graph/node_modules/b/node_modules/d");
+
+    assert_eq!(report.len(), 1);
+    assert_eq!(report[0].name, "d");
+    assert_eq!(report[0].versions, vec!["1.0.0", "2.0.0"]);
+    assert_eq!(report[0].copies.iter().map(|copy| copy.size).collect::<Vec<u64>>(), vec![hoisted, nested]);
+    assert_eq!(report[0].wasted_bytes(), hoisted.min(nested));
+    assert!(duplicate_version_report(&fixture("tests/fixtures/exports")).is_empty());
+}
+
+#[test]
+fn it_should_resolve_directory_exports() {
+    let options = fixture("tests/fixtures/exports");
+    let root = Path::new("tests/fixtures/exports/node_modules/directory-mount");
+    let exports: ExportValue = serde_json::from_str(r#"{ "./lib/": "./src/lib/" }"#).unwrap();
+
+    assert_eq!(exports.resolve("./lib/foo", &["default"]).as_deref(), Some("./src/lib/foo"));
+    assert_eq!(exports.resolve("./lib/../index.js", &["default"]), None);
+    assert_eq!(get_package_export("directory-mount", "./lib/foo", &[], &options), Some(root.join("./src/lib/foo.js")));
+    assert_eq!(get_package_export("directory-mount", "./lib/bar.cjs", &[], &options), Some(root.join("./src/lib/bar.cjs")));
+    assert_eq!(get_package_export("directory-mount", "./src/lib/foo.js", &[], &options), None);
+}
+
+#[test]
+fn it_should_get_peer_ranges() {
+    let pkg_info = get_package_info("with-peers", fixture("tests/fixtures/peers")).unwrap();
+    let required: Vec<&str> = pkg_info.peer_ranges().into_keys().filter(|name| !pkg_info.is_optional_peer(name)).collect();
+
+    assert_eq!(pkg_info.peer_ranges(), HashMap::from([("react", "^18.0.0"), ("react-dom", "^18.0.0")]));
+    assert_eq!(required, vec!["react"]);
+    assert!(get_package_info("consola", Options::default()).unwrap().peer_ranges().is_empty());
+}
+
+#[test]
+fn it_should_expand_the_bin_directory() {
+    let pkg_info = get_package_info("bin-directory", fixture("tests/fixtures/bin")).unwrap();
+    let scripts = pkg_info.root_path.join("./scripts");
+
+    assert_eq!(pkg_info.bin_paths(), HashMap::from([
+        (String::from("build"), scripts.join("build.js")),
+        (String::from("serve"), scripts.join("serve")),
+    ]));
+    assert_eq!(pkg_info.bin_path("build"), Some(scripts.join("build.js")));
+    assert!(get_package_info("consola", Options::default()).unwrap().bin_paths().is_empty());
+}
+
+#[test]
+fn it_should_strip_the_entry_suffix() {
+    let pkg_info = get_package_info("query-main", fixture("tests/fixtures/main-field")).unwrap();
+
+    assert_eq!(pkg_info.package_entry, pkg_info.root_path.join("dist/index.js"));
+    assert_eq!(pkg_info.entry_suffix(), Some("?v=3"));
+    assert_eq!(get_package_info("consola", Options::default()).unwrap().entry_suffix(), None);
+}
+
+#[test]
+fn it_should_strip_leading_dot_segments_from_manifest_paths() {
+    let pkg_info = get_package_info("dot-main", fixture("tests/fixtures/main-field")).unwrap();
+    let bin = &pkg_info.bin_paths()["dot-main"];
+
+    assert_eq!(pkg_info.package_entry, pkg_info.root_path.join("lib").join("index.js"));
+    assert!(!pkg_info.package_entry.to_string_lossy().contains("/./"));
+    assert!(!bin.to_string_lossy().contains("/./"));
+
+    let pkg_info = get_package_info("consola", Options::default()).unwrap();
+    let utils = get_package_export("consola", "./utils", &[], &Options::default()).unwrap();
+
+    assert!(!utils.to_string_lossy().contains("/./"));
+    assert!(utils.starts_with(&pkg_info.root_path));
+}
+
+#[cfg(windows)]
+#[test]
+fn it_should_use_the_windows_separator_in_manifest_paths() {
+    let pkg_info = get_package_info("dot-main", fixture("tests/fixtures/main-field")).unwrap();
+    let relative = pkg_info.package_entry.strip_prefix(&pkg_info.root_path).unwrap();
+
+    assert_eq!(relative.to_string_lossy(), "lib\\index.js");
+}
+
+#[test]
+fn it_should_read_the_entry() {
+    let pkg_info = get_package_info("query-main", fixture("tests/fixtures/main-field")).unwrap();
+    let missing = PackageInfo { package_entry: pkg_info.root_path.join("missing.js"), ..pkg_info.clone() };
+
+    assert_eq!(pkg_info.read_entry().unwrap(), "module.exports = {};\n");
+    assert_eq!(missing.read_entry().unwrap_err().kind(), std::io::ErrorKind::NotFound);
+}
+
+#[test]
+fn it_should_tell_whether_a_package_has_bin() {
+    let has_bin = |name: &str| get_package_info(name, fixture("tests/fixtures/bin")).unwrap().has_bin();
+
+    assert!(has_bin("bin-map"));
+    assert!(has_bin("@scope/bin-string"));
+    assert!(has_bin("bin-directory"));
+    assert!(has_bin("bin-array"));
+    assert!(!has_bin("empty-bin"));
+    assert!(!get_package_info("consola", Options::default()).unwrap().has_bin());
+}
+
+#[test]
+fn it_should_get_reachable_dependencies() {
+    let options = fixture("tests/fixtures/graph");
+    let a = get_package_info("a", fixture("tests/fixtures/graph")).unwrap();
+    let declares_missing = get_package_info("declares-missing", fixture("tests/fixtures/graph")).unwrap();
+
+    assert_eq!(reachable_dependencies(&a, &options), HashSet::from(["a", "b", "c", "d", "e"].map(String::from)));
+    assert_eq!(reachable_dependencies(&declares_missing, &options), HashSet::from(["d", "e"].map(String::from)));
+    assert_eq!(unreachable_dependencies(&declares_missing, &options), vec!["not-installed"]);
+    assert!(unreachable_dependencies(&a, &options).is_empty());
+}
+
+#[test]
+fn it_should_get_nested_config_by_path() {
+    let pkg_info = get_package_info("tool-config", fixture("tests/fixtures/extra-fields")).unwrap();
+
+    assert_eq!(pkg_info.get_path("vitest.coverage.provider"), Some(&serde_json::json!("v8")));
+    assert_eq!(pkg_info.get_path("vitest.coverage.reporters.1"), Some(&serde_json::json!("html")));
+    assert_eq!(pkg_info.get_path("vitest.coverage.missing"), None);
+    assert_eq!(pkg_info.get_path("vitest.coverage.provider.name"), None);
+    assert_eq!(pkg_info.get_path("name"), None);
+}
+
+#[test]
+fn it_should_get_types_without_a_runtime_entry() {
+    let options = fixture("tests/fixtures/types-only");
+
+    assert_eq!(get_package_info("broken-runtime", fixture("tests/fixtures/types-only")), None);
+    assert_eq!(
+        get_types_entry("broken-runtime", &options),
+        Some(Path::new("tests/fixtures/types-only/node_modules/broken-runtime/dist/index.d.ts").to_path_buf())
+    );
+    assert_eq!(get_types_entry("consola", &Options::default()), get_package_info("consola", Options::default()).unwrap().types_entry());
+}
+
+#[test]
+fn it_should_get_the_workspace_graph() {
+    let edge = |from: &str, to: &str| (from.to_string(), to.to_string());
+
+    assert_eq!(workspace_graph(&fixture("tests/fixtures/workspace")), vec![edge("app", "utils")]);
+    assert_eq!(workspace_graph(&fixture("tests/fixtures/workspace-versions")), vec![
+        edge("app", "core"),
+        edge("app", "utils"),
+        edge("cli", "app"),
+        edge("cli", "core"),
+    ]);
+}
+
+#[test]
+fn it_should_order_workspace_builds() {
+    let names = |packages: Vec<PackageInfo>| -> Vec<String> { packages.into_iter().map(|pkg| pkg.name).collect() };
+
+    assert_eq!(names(workspace_build_order(&fixture("tests/fixtures/workspace-versions")).unwrap()), vec!["core", "utils", "app", "cli"]);
+
+    let cycle = workspace_build_order(&fixture("tests/fixtures/workspace-cycle")).unwrap_err();
+
+    assert_eq!(cycle, CycleError { packages: vec![String::from("x"), String::from("y"), String::from("z")] });
+    assert_eq!(cycle.to_string(), "Dependency cycle: x -> y -> z -> x");
+}
+
+#[test]
+fn it_should_detect_phantom_dependencies() {
+    let options = fixture("tests/fixtures/graph");
+    let b = get_package_info("b", fixture("tests/fixtures/graph")).unwrap();
+
+    assert!(is_resolvable_but_undeclared(&b, "e", &options));
+    assert!(is_resolvable_but_undeclared(&b, "a", &options));
+    assert!(!is_resolvable_but_undeclared(&b, "c", &options));
+    assert!(!is_resolvable_but_undeclared(&b, "d", &options));
+    assert!(!is_resolvable_but_undeclared(&b, "missing", &options));
+}
+
+#[test]
+fn it_should_cache_scans() {
+    let root = std::env::temp_dir().join("npm_pkg-scan-cache");
+    let node_modules = root.join("node_modules");
+    let write_package = |name: &str, version: &str| {
+        fs::create_dir_all(node_modules.join(name)).unwrap();
+        fs::write(node_modules.join(name).join("package.json"), format!(r#"{{ "name": "{}", "version": "{}", "main": "package.json" }}"#, name, version)).unwrap();
+    };
+    let settle = || fs::File::open(&node_modules).unwrap().set_modified(std::time::SystemTime::now() - std::time::Duration::from_secs(60)).unwrap();
+    let options = Options { anchor: Anchor::Custom(root.clone()), scan_cache: true, ..Default::default() };
+    let versions = || -> Vec<(String, String)> { list_installed_packages(&options).into_iter().map(|pkg| (pkg.name, pkg.version)).collect() };
+
+    let _ = fs::remove_dir_all(&root);
+    write_package("a", "1.0.0");
+    settle();
+    assert_eq!(versions(), vec![(String::from("a"), String::from("1.0.0"))]);
+
+    // Edits inside a package do not change the fingerprint, so this is the cached scan.
+    write_package("a", "2.0.0");
+    assert_eq!(versions(), vec![(String::from("a"), String::from("1.0.0"))]);
+
+    // Adding a package touches `node_modules`, which invalidates the cached scan.
+    write_package("b", "1.0.0");
+    assert_eq!(versions(), vec![(String::from("a"), String::from("2.0.0")), (String::from("b"), String::from("1.0.0"))]);
+
+    // A scan of a `node_modules` modified within the mtime granularity is not cached.
+    write_package("a", "3.0.0");
+    assert_eq!(versions()[0].1, "3.0.0");
+
+    settle();
+    assert_eq!(versions()[0].1, "3.0.0");
+    write_package("a", "4.0.0");
+    assert_eq!(versions()[0].1, "3.0.0");
+
+    invalidate_scan_cache();
+    assert_eq!(versions()[0].1, "4.0.0");
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn it_should_get_the_integrity() {
+    let registry = get_package_info("left-pad", fixture("tests/fixtures/registry")).unwrap();
+    let local = get_package_info("consola", Options::default()).unwrap();
+
+    assert_eq!(registry.integrity(), Some("sha512-XI5MPzVNApjAyhQzphX8BkmKsKUxD4LdyK24iZeQEMsSA9MrPc8/KlTCrrk3h/pvl+f/Gh5w62/InkfRD9EU1m/w=="));
+    assert_eq!(local.integrity(), None);
+}
+
+#[test]
+fn it_should_retain_the_raw_json() {
+    let retained = get_package_info("consola", Options { retain_raw: true, ..Default::default() }).unwrap();
+    let dropped = get_package_info("consola", Options::default()).unwrap();
+
+    assert_eq!(retained.raw_json, Some(fs::read_to_string(&retained.package_json_path).unwrap()));
+    assert_eq!(dropped.raw_json, None);
+}
+
+#[test]
+fn it_should_treat_root_conditions_as_the_root_export() {
+    let pkg_info = get_package_info("mitt", fixture("tests/fixtures/exports")).unwrap();
+    let root = &pkg_info.root_path;
+
+    assert_eq!(pkg_info.package_entry, root.join("./dist/mitt.mjs"));
+    assert_eq!(pkg_info.entry_for(Target::Node, &[]), Some(root.join("./dist/mitt.mjs")));
+    assert_eq!(pkg_info.entry_for(Target::Browser, &[]), Some(root.join("./dist/mitt.mjs")));
+    assert_eq!(pkg_info.entry_for(Target::Node, &["require"]), Some(root.join("./dist/mitt.js")));
+    assert_eq!(pkg_info.types_entry(), Some(root.join("./index.d.ts")));
+    assert_eq!(get_package_export("mitt", "./dist/mitt.js", &[], &fixture("tests/fixtures/exports")), None);
+}
+
+#[test]
+fn it_should_resolve_packages_hoisted_above_cwd() {
+    let pkg_info = get_package_info("hoisted-dep", fixture("tests/fixtures/hoisted/packages/app")).unwrap();
+
+    assert_eq!(pkg_info.root_path, Path::new("tests/fixtures/hoisted/node_modules/hoisted-dep"));
+    assert!(is_package_exists("consola", &fixture("tests/fixtures/hoisted/packages/app")));
+    assert!(!is_package_exists("missing", &fixture("tests/fixtures/hoisted/packages/app")));
+}
+
+#[test]
+fn it_should_tell_same_installs() {
+    let linked = get_package_info("linked", fixture("tests/fixtures/symlinks")).unwrap();
+    let other = get_package_info("linked", fixture("tests/fixtures/symlinks/other")).unwrap();
+    let consola = get_package_info("consola", Options::default()).unwrap();
+
+    assert_ne!(linked.root_path, other.root_path);
+    assert!(linked.same_install(&other));
+    assert!(linked.same_install(&linked));
+    assert!(!linked.same_install(&consola));
+}
+
+#[test]
+fn it_should_tell_why_a_package_cannot_be_loaded() {
+    let error = |name: &str, cwd: &'static str| try_get_package_info(name, fixture(cwd)).unwrap_err();
+
+    assert!(matches!(error("../escape", "tests/fixtures/scan-errors"), PackageError::InvalidName { name } if name == "../escape"));
+    assert!(matches!(error("missing", "tests/fixtures/scan-errors"), PackageError::NotFound { name, .. } if name == "missing"));
+    assert!(matches!(error("broken", "tests/fixtures/scan-errors"), PackageError::ParseFailed { path, .. } if path.ends_with("broken/package.json")));
+    assert!(matches!(error("unversioned", "tests/fixtures/scan-errors"), PackageError::MissingVersion { .. }));
+    assert!(matches!(error("no-entry", "tests/fixtures/partial"), PackageError::MissingEntry { .. }));
+    assert_eq!(try_get_package_info("good", fixture("tests/fixtures/scan-errors")).ok(), get_package_info("good", fixture("tests/fixtures/scan-errors")));
+}
+
+#[test]
+fn it_should_not_look_up_names_outside_node_modules() {
+    let options = fixture("tests/fixtures/traversal");
+
+    assert_eq!(get_package_version("inside", &options).as_deref(), Some("1.0.0"));
+    assert_eq!(get_package_version("../escape", &options), None);
+    assert_eq!(get_package_json_path("../escape", &options), None);
+    assert!(!is_package_exists("../escape", &options));
+    assert!(resolve_all_locations("../escape", &options).is_empty());
+}
+
+#[test]
+fn it_should_list_the_directories_searched() {
+    let Err(PackageError::NotFound { searched, .. }) = try_get_package_info("missing", fixture("tests/fixtures/graph/node_modules/b")) else {
+        panic!("expected NotFound");
+    };
+
+    assert_eq!(searched[..3], [
+        Path::new("tests/fixtures/graph/node_modules/b/node_modules"),
+        Path::new("tests/fixtures/graph/node_modules"),
+        Path::new("tests/fixtures/node_modules"),
+    ]);
+    assert_eq!(searched.last().unwrap(), Path::new("/node_modules"));
+
+    let denied = Options { denied_packages: Some(HashSet::from([String::from("consola")])), ..Default::default() };
+
+    assert!(matches!(try_get_package_info("consola", denied), Err(PackageError::NotFound { searched, .. }) if searched.is_empty()));
+}
+
+#[test]
+fn it_should_resolve_the_react_native_entry() {
+    let pkg_info = get_package_info("react-native-field", fixture("tests/fixtures/exports")).unwrap();
+    let browser_only = get_package_info("browser-field", fixture("tests/fixtures/exports")).unwrap();
+    let root = &pkg_info.root_path;
+
+    assert_eq!(pkg_info.package_json.react_native.as_deref(), Some("./native.js"));
+    assert_eq!(pkg_info.entry_for(Target::ReactNative, &[]), Some(root.join("native.js")));
+    assert_eq!(pkg_info.entry_for(Target::Browser, &[]), Some(root.join("browser.js")));
+    assert_eq!(pkg_info.entry_for(Target::Node, &[]), Some(root.join("index.js")));
+    assert_eq!(browser_only.entry_for(Target::ReactNative, &[]), Some(browser_only.root_path.join("browser.js")));
+}
+
+#[test]
+fn it_should_default_the_entry_to_index() {
+    let entry = |name: &str| get_package_info(name, fixture("tests/fixtures/main-field")).map(|pkg| pkg.package_entry);
+    let root = Path::new("tests/fixtures/main-field/node_modules");
+
+    assert_eq!(entry("no-main"), Some(root.join("no-main/index.js")));
+    assert_eq!(entry("index-json"), Some(root.join("index-json/index.json")));
+    assert_eq!(entry("index-node"), Some(root.join("index-node/index.node")));
+    assert_eq!(get_package_info("no-entry", fixture("tests/fixtures/partial")), None);
+}
+
+#[test]
+fn it_should_verify_dependencies_installed() {
+    let app = get_package_info("app", fixture("tests/fixtures/verify")).unwrap();
+    let names = |dependencies: &[ResolvedDependency]| -> Vec<String> { dependencies.iter().map(|dependency| dependency.name.clone()).collect() };
+
+    let report = verify_dependencies_installed(&app, &fixture("tests/fixtures/verify"));
+
+    assert_eq!(names(&report.resolved), vec!["linked", "ok"]);
+    assert_eq!(report.missing, vec!["gone"]);
+    assert_eq!(report.out_of_range, vec![ResolvedDependency {
+        name: String::from("old"),
+        range: String::from("^2.0.0"),
+        version: String::from("1.0.0"),
+        root_path: Path::new("tests/fixtures/verify/node_modules/old").to_path_buf(),
+    }]);
+
+    let report = verify_dependencies_installed(&app, &Options { include_peers: true, ..fixture("tests/fixtures/verify") });
+
+    assert_eq!(names(&report.resolved), vec!["linked", "ok", "peer-ok"]);
+    assert_eq!(report.missing, vec!["gone", "peer-gone"]);
+}
+
+#[test]
+fn it_should_resolve_dependencies_without_runtime_entry() {
+    let options = fixture("tests/fixtures/types-only");
+    let app = get_package_info("typed-app", fixture("tests/fixtures/types-only")).unwrap();
+    let optional = get_package_info("typed-optional", fixture("tests/fixtures/types-only")).unwrap();
+
+    let report = verify_dependencies_installed(&app, &options);
+
+    assert!(report.missing.is_empty());
+    assert_eq!(report.resolved.iter().map(|dependency| dependency.name.as_str()).collect::<Vec<_>>(), vec!["broken-runtime"]);
+    assert_eq!(reachable_dependencies(&app, &options), HashSet::from([String::from("broken-runtime")]));
+    assert!(unreachable_dependencies(&app, &options).is_empty());
+    assert!(matches!(resolve_optional(&optional, "broken-runtime", &options), OptionalResolution::Present(pkg) if pkg.version == "1.0.0"));
+}
+
+#[test]
+fn it_should_not_leak_unknown_keys_into_exports_or_bin() {
+    let pkg_json: PackageJSON = serde_json::from_str(r#"{
+        "name": "unknown-keys",
+        "repository": { "type": "git", "url": "https://github.com/user/repo.git" },
+        "funding": "https://example.com/sponsor"
+    }"#).unwrap();
+
+    assert_eq!(pkg_json.exports, None);
+    assert_eq!(pkg_json.bin, None);
+    assert_eq!(pkg_json.funding, Some(Funding::String(String::from("https://example.com/sponsor"))));
+    assert!(pkg_json.extra.is_empty());
+
+    let roundtrip: PackageJSON = serde_json::from_str(&serde_json::to_string(&pkg_json).unwrap()).unwrap();
+
+    assert_eq!(roundtrip, pkg_json);
+}
+
+#[test]
+fn it_should_normalize_the_license_expression() {
+    let license = |json: &str| PackageInfo { package_json: serde_json::from_str(json).unwrap(), ..Default::default() }.license_expression();
+
+    assert_eq!(license(r#"{ "license": "MIT" }"#).as_deref(), Some("MIT"));
+    assert_eq!(license(r#"{ "license": "(MIT OR Apache-2.0)" }"#).as_deref(), Some("MIT OR Apache-2.0"));
+    assert_eq!(license(r#"{ "license": "(MIT AND BSD-3-Clause) OR (ISC)" }"#).as_deref(), Some("(MIT AND BSD-3-Clause) OR (ISC)"));
+    assert_eq!(license(r#"{ "license": { "type": "ISC", "url": "https://opensource.org/licenses/ISC" } }"#).as_deref(), Some("ISC"));
+    assert_eq!(license(r#"{ "licenses": [{ "type": "MIT" }, { "type": "Apache-2.0" }] }"#).as_deref(), Some("MIT OR Apache-2.0"));
+    assert_eq!(license(r#"{ "licenses": [{ "type": "MIT OR ISC" }, "GPL-2.0"] }"#).as_deref(), Some("(MIT OR ISC) OR GPL-2.0"));
+    assert_eq!(license(r#"{ "license": "UNLICENSED" }"#), None);
+    assert_eq!(license(r#"{ "license": "SEE LICENSE IN LICENSE.txt" }"#), None);
+    assert_eq!(license("{}"), None);
+}
+
+#[test]
+fn it_should_keep_the_object_form_of_the_license() {
+    let json = serde_json::json!({ "license": { "type": "ISC", "url": "https://opensource.org/licenses/ISC" } });
+    let pkg_json: PackageJSON = serde_json::from_value(json.clone()).unwrap();
+
+    assert_eq!(pkg_json.license, Some(License::Object { r#type: Some(String::from("ISC")), url: Some(String::from("https://opensource.org/licenses/ISC")) }));
+    assert_eq!(serde_json::to_value(&pkg_json).unwrap(), json);
+    assert_eq!(serde_json::to_value(pkg_json.to_published()).unwrap(), json);
+}
+
+#[test]
+fn it_should_resolve_the_entry_with_the_condition_list() {
+    let entry = |conditions: &[&str]| {
+        let options = Options { conditions: conditions.iter().map(|condition| condition.to_string()).collect(), ..fixture("tests/fixtures/exports") };
+
+        get_package_info("nested-conditions", options).map(|pkg| pkg.package_entry.file_name().unwrap().to_string_lossy().into_owned())
+    };
+
+    assert_eq!(get_package_info("nested-conditions", fixture("tests/fixtures/exports")).unwrap().package_entry.file_name().unwrap(), "x.mjs");
+    assert_eq!(entry(&["node", "require"]).as_deref(), Some("x.cjs"));
+    assert_eq!(entry(&["development", "node", "import"]).as_deref(), Some("x.mjs"));
+    assert_eq!(entry(&["development", "import"]).as_deref(), Some("dev.js"));
+    assert_eq!(entry(&["browser", "default"]).as_deref(), Some("fallback.js"));
+    assert_eq!(entry(&["node", "default"]).as_deref(), Some("fallback.js"));
+}
+
+#[test]
+fn it_should_resolve_custom_entry_fields() {
+    let options = fixture("tests/fixtures/exports");
+    let pkg_info = get_package_info("custom-fields", fixture("tests/fixtures/exports")).unwrap();
+    let root = &pkg_info.root_path;
+
+    assert_eq!(pkg_info.custom_entry("svelte", &options), Some(root.join("src/index.svelte")));
+    assert_eq!(pkg_info.custom_entry("style", &options), Some(root.join("styles/main.json")));
+    assert_eq!(pkg_info.custom_entry("main", &options), Some(root.join("index.js")));
+    assert_eq!(pkg_info.custom_entry("sass", &options), None);
+    assert_eq!(pkg_info.custom_entry("sideEffects", &options), None);
+    assert_eq!(pkg_info.custom_entry("missing", &options), None);
+}
+
+#[test]
+fn it_should_resolve_package_subpaths() {
+    let options = fixture("tests/fixtures/exports");
+    let consola = get_package_info("consola", Options::default()).unwrap();
+
+    assert_eq!(get_package_subpath("consola", "utils", &Options::default()), Some(consola.root_path.join("dist/utils.mjs")));
+    assert_eq!(get_package_subpath("consola", "./core", &Options::default()), Some(consola.root_path.join("dist/core.mjs")));
+    assert_eq!(get_package_subpath("consola", "dist/utils.mjs", &Options::default()), None);
+    assert_eq!(get_package_subpath("string-exports", ".", &options), Some(Path::new("tests/fixtures/exports/node_modules/string-exports/index.js").to_path_buf()));
+    assert_eq!(get_package_subpath("string-exports", "other.js", &options), None);
+}
+
+#[test]
+fn it_should_follow_the_pnpm_symlinks() {
+    let options = Options { follow_symlinks: true, ..fixture("tests/fixtures/pnpm") };
+    let store = fs::canonicalize("tests/fixtures/pnpm/node_modules/.pnpm").unwrap();
+    let foo_bar = get_package_info("foo-bar", Options { follow_symlinks: true, ..fixture("tests/fixtures/pnpm") }).unwrap();
+    let scoped = get_package_info("@scope/pkg", options).unwrap();
+
+    assert_eq!(foo_bar.root_path, Path::new("tests/fixtures/pnpm/node_modules/foo-bar"));
+    assert_eq!(foo_bar.real_path, store.join("foo-bar@3.0.0/node_modules/foo-bar"));
+    assert_eq!(foo_bar.version, "3.0.0");
+    assert_eq!(scoped.real_path, store.join("@scope+pkg@2.0.0(react@18.2.0)/node_modules/@scope/pkg"));
+    assert_eq!(get_package_info("foo-bar", fixture("tests/fixtures/pnpm")).unwrap().real_path, foo_bar.root_path);
+}
+
+#[test]
+fn it_should_resolve_via_the_pnpm_store() {
+    let options = fixture("tests/fixtures/pnpm");
+    let version = |name: &str, version: Option<&str>| resolve_via_pnpm_store(name, version, &options).map(|pkg| pkg.version);
+
+    assert_eq!(version("foo", None).as_deref(), Some("1.10.0"));
+    assert_eq!(version("foo", Some("1.0.0")).as_deref(), Some("1.0.0"));
+    assert_eq!(version("foo", Some("2.0.0")), None);
+    assert_eq!(version("foo", Some("file+vendor+foo")).as_deref(), Some("0.1.0"));
+    assert_eq!(version("@scope/pkg", None).as_deref(), Some("2.0.0"));
+    assert_eq!(version("foo-bar", None).as_deref(), Some("3.0.0"));
+    assert_eq!(
+        resolve_via_pnpm_store("foo", None, &options).unwrap().root_path,
+        Path::new("tests/fixtures/pnpm/node_modules/.pnpm/foo@1.10.0_bar@2.0.0/node_modules/foo")
+    );
+}
+
+#[test]
+fn it_should_resolve_wildcard_subpaths() {
+    let options = fixture("tests/fixtures/exports");
+    let root = Path::new("tests/fixtures/exports/node_modules/pattern-exports");
+
+    assert_eq!(get_package_subpath("pattern-exports", "components/Button", &options), Some(root.join("./dist/components/Button.mjs")));
+    assert_eq!(get_package_subpath("pattern-exports", "components/forms/Input", &options), Some(root.join("./dist/components/forms/Input.mjs")));
+    assert_eq!(get_package_subpath("pattern-exports", "components/Special", &options), Some(root.join("./dist/special.mjs")));
+    assert_eq!(get_package_subpath("pattern-exports", "dist/index.mjs", &options), None);
+}
+
+#[test]
+fn it_should_list_the_conditions_required_for_a_subpath() {
+    let options = fixture("tests/fixtures/exports");
+    let paths = |name: &str, subpath: &str| -> Vec<String> {
+        required_conditions_for(name, subpath, &options)
+            .into_iter()
+            .map(|path| path.join("."))
+            .collect()
+    };
+
+    assert_eq!(
+        paths("nested-conditions", "."),
+        vec!["default", "development", "node.import", "node.require"]
+    );
+    assert_eq!(paths("pattern-exports", "components/Button"), vec![""]);
+    assert!(paths("pattern-exports", "components/Missing").is_empty());
+    assert!(paths("nested-conditions", "missing").is_empty());
+}
+
+#[test]
+fn it_should_resolve_internal_imports() {
+    let pkg: PackageJSON = serde_json::from_str(
+        r##"{
+            "imports": {
+                "#env": { "node": "./env.node.js", "default": "./env.js" },
+                "#internal/*": "./src/internal/*.js",
+                "#dep": "dep"
+            }
+        }"##,
+    )
+    .unwrap();
+    let conditions = Options::default().conditions;
+
+    assert_eq!(resolve_import(&pkg, "#env", &conditions).as_deref(), Some("./env.node.js"));
+    assert_eq!(resolve_import(&pkg, "#env", &[String::from("browser"), String::from("default")]).as_deref(), Some("./env.js"));
+    assert_eq!(resolve_import(&pkg, "#internal/foo", &conditions).as_deref(), Some("./src/internal/foo.js"));
+    assert_eq!(resolve_import(&pkg, "#dep", &conditions).as_deref(), Some("dep"));
+    assert_eq!(resolve_import(&pkg, "#missing", &conditions), None);
+    assert_eq!(resolve_import(&pkg, "env", &conditions), None);
+}
+
+#[test]
+fn it_should_anchor_resolution() {
+    let git_root = Options {
+        cwd: Some("tests/fixtures/hoisted/packages/app"),
+        anchor: Anchor::GitRoot,
+        ..Default::default()
+    };
+    let custom = Options {
+        anchor: Anchor::Custom(PathBuf::from("tests/fixtures/exports")),
+        ..Default::default()
+    };
+
+    assert_eq!(node_modules_roots(&git_root)[0], current_dir().unwrap().join("node_modules"));
+    assert!(get_package_info("hoisted-dep", git_root).is_none());
+    assert!(get_package_info("nested-conditions", Options::default()).is_none());
+    assert_eq!(
+        get_package_info("nested-conditions", custom).unwrap().root_path,
+        PathBuf::from("tests/fixtures/exports/node_modules/nested-conditions")
+    );
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn it_should_get_package_info_async() {
+    use npm_pkg::get_package_info_async;
+
+    let dirs = ["tests/fixtures/exports", "tests/fixtures/main-field", "tests/fixtures/verify", "tests/fixtures/bin", "tests/fixtures/graph", "tests/fixtures/module-type"];
+    let lookups: Vec<(&str, PackageInfo)> = dirs
+        .iter()
+        .flat_map(|dir| list_installed_packages(&fixture(dir)).into_iter().map(move |pkg| (*dir, pkg)))
+        .cycle()
+        .take(50)
+        .collect();
+    let packages = futures::future::join_all(lookups.iter().map(|(dir, installed)| get_package_info_async(&installed.name, fixture(dir)))).await;
+
+    assert_eq!(packages.len(), 50);
+
+    for ((dir, installed), pkg) in lookups.iter().zip(packages) {
+        // Scans also list packages without a runtime entry, which lookups never return.
+        assert_eq!(pkg.is_some(), !installed.package_entry.as_os_str().is_empty());
+        assert_eq!(pkg, get_package_info(&installed.name, fixture(dir)));
+    }
+}
+
+#[test]
+fn it_should_cache_package_info() {
+    let root = std::env::temp_dir().join("npm_pkg-package-cache");
+    let package_json = root.join("node_modules/cached/package.json");
+    let write_version = |version: &str| fs::write(&package_json, format!(r#"{{ "name": "cached", "version": "{}", "main": "package.json" }}"#, version)).unwrap();
+    let options = Options { anchor: Anchor::Custom(root.clone()), ..Default::default() };
+    let cache = std::sync::Arc::new(std::sync::Mutex::new(PackageCache::new()));
+
+    fs::create_dir_all(package_json.parent().unwrap()).unwrap();
+    write_version("1.0.0");
+
+    let version = |cache: &std::sync::Mutex<PackageCache>| cache.lock().unwrap().get_or_insert("cached", &options).map(|pkg| pkg.version.clone());
+
+    assert_eq!(version(&cache), Some(String::from("1.0.0")));
+    write_version("2.0.0");
+    assert_eq!(std::thread::scope(|scope| scope.spawn(|| version(&cache)).join().unwrap()), Some(String::from("1.0.0")));
+
+    cache.lock().unwrap().remove("cached");
+    assert_eq!(version(&cache), Some(String::from("2.0.0")));
+    assert_eq!(cache.lock().unwrap().get_or_insert("consola", &Options::default()).map(|pkg| pkg.name.as_str()), Some("consola"));
+    assert_eq!(cache.lock().unwrap().len(), 2);
+
+    cache.lock().unwrap().clear();
+    assert!(cache.lock().unwrap().is_empty());
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn it_should_parse_repository_bugs_contributors_and_funding() {
+    let json = serde_json::json!({
+        "repository": { "type": "git", "url": "https://github.com/user/repo.git", "directory": "packages/a" },
+        "author": "Jane <jane@example.com>",
+        "bugs": { "url": "https://github.com/user/repo/issues" },
+        "contributors": ["Jane <jane@example.com>", { "name": "John", "url": "https://john.example.com" }],
+        "funding": ["https://example.com/sponsor", { "type": "patreon", "url": "https://patreon.com/user" }]
+    });
+    let pkg_json: PackageJSON = serde_json::from_value(json.clone()).unwrap();
+
+    assert_eq!(pkg_json.repository, Some(Repository::Object {
+        r#type: Some(String::from("git")),
+        url: String::from("https://github.com/user/repo.git"),
+        directory: Some(String::from("packages/a")),
+    }));
+    assert_eq!(pkg_json.author, Some(Person::String(String::from("Jane <jane@example.com>"))));
+    assert_eq!(pkg_json.bugs, Some(Bugs::Object { url: Some(String::from("https://github.com/user/repo/issues")), email: None }));
+    assert_eq!(pkg_json.contributors, Some(vec![
+        Person::String(String::from("Jane <jane@example.com>")),
+        Person::Object { name: String::from("John"), email: None, url: Some(String::from("https://john.example.com")) },
+    ]));
+    assert_eq!(pkg_json.funding, Some(Funding::Array(vec![
+        Funding::String(String::from("https://example.com/sponsor")),
+        Funding::Object { r#type: Some(String::from("patreon")), url: String::from("https://patreon.com/user") },
+    ])));
+
+    let serialized = serde_json::to_value(&pkg_json).unwrap();
+
+    for field in ["repository", "author", "bugs", "contributors", "funding"] {
+        assert_eq!(serialized[field], json[field]);
+    }
+
+    let pkg_info = get_package_info("object-author", fixture("tests/fixtures/extra-fields")).unwrap();
+
+    assert_eq!(pkg_info.package_json.author, Some(Person::Object {
+        name: String::from("Jane"),
+        email: Some(String::from("jane@example.com")),
+        url: Some(String::from("https://jane.example.com")),
+    }));
+}
+
+#[test]
+fn it_should_check_an_installed_version_against_a_range() {
+    let satisfies = |name: &str, range: &str| package_satisfies(name, range, &fixture("tests/fixtures/satisfies"));
+
+    assert_eq!(satisfies("stable", "^1.2.0"), Some(true));
+    assert_eq!(satisfies("stable", "~1.3.0"), Some(false));
+    assert_eq!(satisfies("stable", "~1.4.0"), Some(true));
+    assert_eq!(satisfies("stable", "^0.9.0 || >=1.4.2 <2"), Some(true));
+    assert_eq!(satisfies("beta", "^2.0.0-beta.1"), Some(true));
+    assert_eq!(satisfies("beta", "^1.0.0 || >=2.0.0-alpha"), Some(true));
+    assert_eq!(satisfies("beta", ">=1.0.0"), Some(false));
+    assert_eq!(satisfies("loose", "*"), None);
+    assert_eq!(satisfies("missing", "*"), None);
+    assert_eq!(package_satisfies("@types/foo", "^1.0.0", &fixture("tests/fixtures/installed")), Some(true));
+}
+
+#[test]
+fn it_should_normalize_the_homepage_url() {
+    let homepage = |json: &str| PackageInfo { package_json: serde_json::from_str(json).unwrap(), ..Default::default() }.homepage_url();
+
+    assert_eq!(homepage(r#"{ "homepage": "https://example.com/docs" }"#).as_deref(), Some("https://example.com/docs"));
+    assert_eq!(homepage(r#"{ "homepage": "github:user/repo" }"#).as_deref(), Some("https://github.com/user/repo"));
+    assert_eq!(homepage(r#"{ "homepage": "gitlab:group/repo" }"#).as_deref(), Some("https://gitlab.com/group/repo"));
+    assert_eq!(homepage(r#"{ "homepage": "bitbucket:team/repo" }"#).as_deref(), Some("https://bitbucket.org/team/repo"));
+    assert_eq!(homepage(r#"{ "homepage": "user/repo" }"#).as_deref(), Some("https://github.com/user/repo"));
+    assert_eq!(homepage(r#"{ "repository": "git+ssh://git@github.com/user/repo.git" }"#).as_deref(), Some("https://github.com/user/repo"));
+    assert_eq!(homepage(r#"{ "repository": { "type": "git", "url": "git@gitlab.com:group/repo.git" } }"#).as_deref(), Some("https://gitlab.com/group/repo"));
+    assert_eq!(homepage(r#"{ "repository": "git://github.com/user/repo.git" }"#).as_deref(), Some("https://github.com/user/repo"));
+    assert_eq!(homepage(r#"{ "name": "no-links" }"#), None);
+}
+
+#[test]
+fn it_should_list_installed_packages() {
+    let names: Vec<String> = list_installed_packages(&fixture("tests/fixtures/installed")).into_iter().map(|pkg| pkg.name).collect();
+
+    assert_eq!(names, vec!["@scope/scoped", "@types/foo", "plain"]);
+}
+
+#[test]
+fn it_should_scan_packages_without_runtime_entry() {
+    let options = fixture("tests/fixtures/installed");
+    let (packages, errors) = scan_with_errors(&options);
+    let types = packages.iter().find(|pkg| pkg.name == "@types/foo").unwrap();
+
+    assert!(errors.iter().all(|(_, error)| !matches!(error, PackageError::MissingEntry { .. })));
+    assert_eq!(types.package_entry, PathBuf::new());
+    assert_eq!(types.types_entry(), Some(types.root_path.join("index.d.ts")));
+    assert_eq!(installed_versions(&options)["@types/foo"], vec!["1.2.0"]);
+    assert_eq!(PackageIndex::build(&options).resolve("@types/foo"), None);
+    assert_eq!(PackageIndex::build(&options).resolve_all("@types/foo")[0].version, "1.2.0");
+    assert!(matches!(try_get_package_info("@types/foo", fixture("tests/fixtures/installed")), Err(PackageError::MissingEntry { .. })));
+}
+
+#[test]
+fn it_should_report_subpaths_not_exported() {
+    let options = fixture("tests/fixtures/exports");
+    let error = try_get_package_export("pattern-exports", "./dist/index.mjs", &[], &options).unwrap_err();
+
+    assert!(matches!(&error, PackageError::SubpathNotExported { name, subpath } if name == "pattern-exports" && subpath == "./dist/index.mjs"));
+    assert_eq!(error.to_string(), r#"Package subpath './dist/index.mjs' is not defined by "exports" in pattern-exports"#);
+    assert!(try_get_package_export("pattern-exports", "./components/Button", &[], &options).is_ok());
+    assert!(matches!(try_get_package_export("missing", ".", &[], &options), Err(PackageError::NotFound { .. })));
+}
+
+#[test]
+fn it_should_parse_the_browser_map() {
+    let json = r#"{"browser":{"./node.js":"./browser.js","fs":false}}"#;
+    let pkg_json: PackageJSON = serde_json::from_str(json).unwrap();
+
+    assert_eq!(pkg_json.browser, Some(Browser::Map(HashMap::from([
+        (String::from("./node.js"), BrowserValue::Path(String::from("./browser.js"))),
+        (String::from("fs"), BrowserValue::Disabled(false)),
+    ]))));
+    assert_eq!(pkg_json.browser.as_ref().unwrap().entry(), None);
+
+    let roundtrip: PackageJSON = serde_json::from_str(&serde_json::to_string(&pkg_json).unwrap()).unwrap();
+
+    assert_eq!(roundtrip, pkg_json);
+    assert_eq!(serde_json::to_value(&pkg_json).unwrap()["browser"], serde_json::from_str::<serde_json::Value>(json).unwrap()["browser"]);
+}
+
+#[test]
+fn it_should_resolve_optional_dependencies() {
+    let options = fixture("tests/fixtures/optional");
+    let app = get_package_info("app", fixture("tests/fixtures/optional")).unwrap();
+
+    assert!(matches!(resolve_optional(&app, "native-linux", &options), OptionalResolution::Present(pkg) if pkg.version == "1.0.0"));
+    assert_eq!(resolve_optional(&app, "native-darwin", &options), OptionalResolution::AbsentButOptional);
+    assert_eq!(resolve_optional(&app, "required", &options), OptionalResolution::NotDeclared);
+    assert_eq!(resolve_optional(&app, "unknown", &options), OptionalResolution::NotDeclared);
+}
+
+#[test]
+fn it_should_get_the_registry_for_a_scope() {
+    let registry = |cwd: &'static str, scope: &str| registry_for_scope(scope, &fixture(cwd));
+
+    assert_eq!(registry("tests/fixtures/npmrc", "@acme").as_deref(), Some("https://npm.acme.dev/"));
+    assert_eq!(registry("tests/fixtures/npmrc/packages/app", "@acme").as_deref(), Some("https://npm.acme.dev/app/"));
+    assert_eq!(registry("tests/fixtures/npmrc/packages/app", "other").as_deref(), Some("https://other.example.com/"));
+    assert_eq!(registry("tests/fixtures/npmrc/packages/app", "@unknown").as_deref(), Some("https://registry.example.com/"));
+    assert_eq!(registry("tests/fixtures/workspace", "@acme").as_deref(), Some("https://registry.npmjs.org/"));
+    assert_eq!(registry("tests/fixtures/npmrc", "@"), None);
+}
+
+#[test]
+fn it_should_look_packages_up_from_a_file() {
+    let from_file = Options { from: Some(Path::new("tests/fixtures/hoisted/packages/app/src/index.js")), ..Default::default() };
+    let from_dir = Options { from: Some(Path::new("tests/fixtures/hoisted/packages/app")), ..Default::default() };
+
+    assert_eq!(get_package_info("hoisted-dep", from_file).unwrap().root_path, Path::new("tests/fixtures/hoisted/node_modules/hoisted-dep"));
+    assert!(get_package_info("hoisted-dep", from_dir).is_some());
+    assert!(get_package_info("hoisted-dep", Options::default()).is_none());
+}
+
+#[test]
+fn it_should_resolve_all_locations() {
+    let graph = Path::new("tests/fixtures/graph/node_modules");
+    let from_app = Options { from: Some(Path::new("tests/fixtures/hoisted/packages/app")), ..Default::default() };
+
+    assert_eq!(resolve_all_locations("d", &fixture("tests/fixtures/graph")), vec![graph.join("d"), graph.join("b/node_modules/d")]);
+    assert_eq!(resolve_all_locations("a", &fixture("tests/fixtures/graph")), vec![graph.join("a")]);
+    assert_eq!(resolve_all_locations("hoisted-dep", &from_app), vec![Path::new("tests/fixtures/hoisted/node_modules/hoisted-dep")]);
+    assert!(resolve_all_locations("missing", &fixture("tests/fixtures/graph")).is_empty());
+}
+
+#[test]
+fn it_should_scan_symlink_cycles_once() {
+    let options = fixture("tests/fixtures/symlink-cycle/packages/app");
+    let node_modules = Path::new("tests/fixtures/symlink-cycle/packages/app/node_modules");
+
+    assert_eq!(resolve_all_locations("lib", &options), vec![node_modules.join("lib")]);
+    assert_eq!(resolve_all_locations("app", &options), vec![node_modules.join("lib/node_modules/app")]);
+    assert_eq!(PackageIndex::build(&options).resolve_all("lib").len(), 1);
+    assert_eq!(installed_versions(&options)["lib"], vec!["1.0.0"]);
+    assert!(duplicate_version_report(&options).is_empty());
+}
+
+#[test]
+fn it_should_get_the_package_version_only() {
+    for (cwd, name) in [("tests/fixtures/satisfies", "beta"), ("tests/fixtures/graph", "d"), ("tests/fixtures/partial", "numeric-version")] {
+        assert_eq!(get_package_version(name, &fixture(cwd)), get_package_info(name, fixture(cwd)).map(|pkg| pkg.version));
+    }
+
+    assert_eq!(get_package_version("numeric-version", &fixture("tests/fixtures/partial")).as_deref(), Some("1"));
+}
+
+#[test]
+fn it_should_get_the_types_package_name() {
+    let types = |name: &str| PackageInfo { name: name.to_string(), ..Default::default() }.types_package_name();
+
+    assert_eq!(types("express"), "@types/express");
+    assert_eq!(types("@babel/core"), "@types/babel__core");
+    assert_eq!(types("@jridgewell/sourcemap-codec"), "@types/jridgewell__sourcemap-codec");
+    assert_eq!(types("@types/node"), "@types/node");
+}
+
+#[test]
+fn it_should_round_trip_unknown_fields() {
+    let json = r#"{
+        "name": "custom-config",
+        "version": "1.0.0",
+        "main": "index.js",
+        "publishConfig": { "access": "public", "provenance": true, "customTool": { "channel": "beta" } },
+        "customField": ["kept", 1, null],
+        "packageManager": "pnpm@9.0.0"
+    }"#;
+    let pkg_json: PackageJSON = serde_json::from_str(json).unwrap();
+
+    assert_eq!(pkg_json.publish_config.as_ref().unwrap()["customTool"], serde_json::json!({ "channel": "beta" }));
+    assert_eq!(pkg_json.extra["customField"], serde_json::json!(["kept", 1, null]));
+    assert_eq!(serde_json::to_value(&pkg_json).unwrap(), serde_json::from_str::<serde_json::Value>(json).unwrap());
+}
+
+#[test]
+fn it_should_match_pattern_trailers() {
+    let options = fixture("tests/fixtures/exports");
+    let root = Path::new("tests/fixtures/exports/node_modules/pattern-trailers");
+
+    assert_eq!(get_package_export("pattern-trailers", "./utils/math.js", &[], &options), Some(root.join("./dist/utils/math.js")));
+    assert_eq!(get_package_export("pattern-trailers", "./utils/math", &[], &options), None);
+    assert_eq!(get_package_export("pattern-trailers", "./utils/.js", &[], &options), None);
+}
+
+#[test]
+fn it_should_resolve_from_a_custom_modules_dir() {
+    let web_modules = || Options { modules_dir: Some("web_modules"), ..fixture("tests/fixtures/modules-dir") };
+    let pkg_info = get_package_info("web-pkg", web_modules()).unwrap();
+
+    assert_eq!(pkg_info.root_path, Path::new("tests/fixtures/modules-dir/web_modules/web-pkg"));
+    assert_eq!(list_installed_packages(&web_modules()).len(), 1);
+    assert!(get_package_info("web-pkg", fixture("tests/fixtures/modules-dir")).is_none());
+    assert!(get_package_info("consola", web_modules()).is_none());
+    assert_eq!(
+        resolve_via_pnpm_store("web-store", None, &web_modules()).unwrap().root_path,
+        Path::new("tests/fixtures/modules-dir/web_modules/.pnpm/web-store@1.0.0/web_modules/web-store")
+    );
+}
+
+#[test]
+fn it_should_serialize_canonically() {
+    let canonical = || get_package_info("consola", Options::default()).unwrap().to_canonical_json();
+    let json = canonical();
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(json, canonical());
+    assert_eq!(value["root_path"], ".");
+    assert_eq!(value["package_json_path"], "package.json");
+    assert_eq!(value["package_entry"], "dist/index.mjs");
+    assert!(json.find(r#""dependencies""#) < json.find(r#""devDependencies""#));
+    assert!(!json.contains(&*current_dir().unwrap().to_string_lossy()));
+}
+
+#[test]
+fn it_should_resolve_from_a_package_index() {
+    let options = fixture("tests/fixtures/graph");
+    let index = PackageIndex::build(&options);
+    let locations: Vec<PathBuf> = index.resolve_all("d").iter().map(|pkg| pkg.root_path.clone()).collect();
+
+    assert_eq!(index.resolve("d"), get_package_info("d", fixture("tests/fixtures/graph")).as_ref());
+    assert_eq!(locations, resolve_all_locations("d", &options));
+    assert_eq!(index.resolve("missing"), None);
+    assert!(index.resolve_all("missing").is_empty());
+    assert_eq!(PackageIndex::from_json(&index.to_json().unwrap()).unwrap(), index);
+
+    let denied = Options { denied_packages: Some(HashSet::from([String::from("d")])), ..fixture("tests/fixtures/graph") };
+
+    assert_eq!(PackageIndex::build(&denied).resolve("d"), None);
+}
+
+#[test]
+fn it_should_check_engine_compatibility() {
+    let pkg_info = get_package_info("consola", Options::default()).unwrap();
+    let node = |version: &str| engine_satisfies(&pkg_info, "node", version);
+
+    assert_eq!(node("14.18.0"), Some(true));
+    assert_eq!(node("14.99.1"), Some(true));
+    assert_eq!(node("14.17.6"), Some(false));
+    assert_eq!(node("15.14.0"), Some(false));
+    assert_eq!(node("16.10.0"), Some(true));
+    assert_eq!(node("v20.5.1"), Some(true));
+    assert_eq!(node("latest"), None);
+    assert_eq!(engine_satisfies(&pkg_info, "deno", "1.0.0"), None);
+    assert_eq!(engine_satisfies(&PackageInfo::default(), "node", "20.0.0"), None);
+}
+
+#[test]
+fn it_should_name_array_bin_commands_after_their_files() {
+    let pkg_info = get_package_info("bin-array", fixture("tests/fixtures/bin")).unwrap();
+
+    assert_eq!(pkg_info.package_json.bin, Some(BinType::Array(vec![String::from("./bin/array-a.js"), String::from("bin/array-b")])));
+    assert_eq!(pkg_info.bin_paths(), HashMap::from([
+        (String::from("array-a"), pkg_info.root_path.join("bin/array-a.js")),
+        (String::from("array-b"), pkg_info.root_path.join("bin/array-b")),
+    ]));
+    assert_eq!(pkg_info.bin_path("bin-array"), None);
+}
+
+#[test]
+fn it_should_prefer_exports_then_module_for_esm_then_main() {
+    let entry = |name: &str| {
+        let pkg_info = get_package_info(name, fixture("tests/fixtures/entry-precedence")).unwrap();
+
+        pkg_info.package_entry.strip_prefix(&pkg_info.root_path).unwrap().to_path_buf()
+    };
+
+    assert_eq!(entry("commonjs-module"), Path::new("lib/index.js"));
+    assert_eq!(entry("untyped-module"), Path::new("lib/index.js"));
+    assert_eq!(entry("esm-module"), Path::new("esm/index.mjs"));
+    assert_eq!(entry("exports-module"), Path::new("dist/index.mjs"));
+    assert_eq!(get_package_info("exports-module", fixture("tests/fixtures/entry-precedence")).unwrap().entry_suffix(), None);
+
+    let options = fixture("tests/fixtures/entry-precedence");
+    let (packages, _) = scan_with_errors(&options);
+    let subpath_exports = packages.iter().find(|pkg| pkg.name == "subpath-exports-main").unwrap();
+
+    assert_eq!(subpath_exports.package_entry, PathBuf::new());
+    assert_eq!(subpath_exports.entry_for(Target::Node, &[]), None);
+    assert!(matches!(try_get_package_export("subpath-exports-main", ".", &[], &options), Err(PackageError::SubpathNotExported { .. })));
+    assert!(matches!(try_get_package_info("subpath-exports-main", fixture("tests/fixtures/entry-precedence")), Err(PackageError::MissingEntry { .. })));
+}
+
+#[test]
+fn it_should_explain_a_resolution() {
+    let graph = Path::new("tests/fixtures/graph/node_modules");
+    let versions = |packages: &[PackageInfo]| packages.iter().map(|pkg| pkg.version.clone()).collect::<Vec<String>>();
+
+    let hoisted = explain_resolution("d", Path::new("tests/fixtures/graph"), &Options::default());
+
+    assert_eq!(hoisted.package.map(|pkg| pkg.version).as_deref(), Some("1.0.0"));
+    assert_eq!(hoisted.node_modules.as_deref(), Some(graph));
+    assert_eq!(hoisted.subpath, ".");
+    assert_eq!(hoisted.matched_key, None);
+    assert_eq!(hoisted.file, Some(graph.join("d/index.js")));
+    assert_eq!(versions(&hoisted.shadowed), vec!["2.0.0"]);
+
+    let nested = explain_resolution("d/index", &graph.join("b/index.js"), &Options::default());
+
+    assert_eq!(nested.package.map(|pkg| pkg.version).as_deref(), Some("2.0.0"));
+    assert_eq!(nested.node_modules, Some(graph.join("b/node_modules")));
+    assert_eq!(nested.file, Some(graph.join("b/node_modules/d/index.js")));
+    assert_eq!(versions(&nested.shadowed), vec!["1.0.0"]);
+
+    let require = Options { conditions: vec![String::from("require")], ..Default::default() };
+    let consola = explain_resolution("consola/utils", &current_dir().unwrap(), &require);
+
+    assert_eq!(consola.name, "consola");
+    assert_eq!(consola.subpath, "./utils");
+    assert_eq!(consola.matched_key.as_deref(), Some("./utils"));
+    assert_eq!(consola.matched_conditions, vec!["require"]);
+    assert_eq!(consola.file, Some(current_dir().unwrap().join("node_modules/consola/dist/utils.cjs")));
+
+    let exports = Path::new("tests/fixtures/exports/node_modules");
+    let manifest = explain_resolution("directory-mount/package.json", Path::new("tests/fixtures/exports"), &Options::default());
+
+    assert_eq!(manifest.file, Some(exports.join("directory-mount/package.json")));
+    assert_eq!(manifest.matched_key, None);
+    assert_eq!(explain_resolution("blocked-manifest/package.json", Path::new("tests/fixtures/exports"), &Options::default()).file, None);
+
+    let subpath_only = explain_resolution("subpath-only/lib/x", Path::new("tests/fixtures/exports"), &Options::default());
+
+    assert_eq!(subpath_only.package.map(|pkg| pkg.name).as_deref(), Some("subpath-only"));
+    assert_eq!(subpath_only.matched_key.as_deref(), Some("./lib/x"));
+    assert_eq!(subpath_only.file, get_package_export("subpath-only", "./lib/x", &[], &fixture("tests/fixtures/exports")));
+    assert_eq!(explain_resolution("subpath-only", Path::new("tests/fixtures/exports"), &Options::default()).file, None);
+
+    let missing = explain_resolution("@scope/missing/sub", Path::new("tests/fixtures/graph"), &Options::default());
+
+    assert_eq!((missing.name.as_str(), missing.subpath.as_str()), ("@scope/missing", "./sub"));
+    assert_eq!(missing.package, None);
+    assert_eq!(missing.file, None);
+}
+
+#[test]
+fn it_should_tell_the_module_kind_of_the_entry() {
+    let kind = |name: &str| get_package_info(name, fixture("tests/fixtures/module-kind")).unwrap().module_kind;
+
+    assert_eq!(kind("mjs-entry"), ModuleKind::Esm);
+    assert_eq!(kind("cjs-entry"), ModuleKind::Cjs);
+    assert_eq!(kind("esm-js"), ModuleKind::Esm);
+    assert_eq!(kind("untyped-js"), ModuleKind::Cjs);
+    assert_eq!(get_package_info("commonjs-module", fixture("tests/fixtures/entry-precedence")).unwrap().module_kind, ModuleKind::Cjs);
+}
+
+#[test]
+fn it_should_resolve_exports_of_a_package_without_root_entry() {
+    let options = fixture("tests/fixtures/exports");
+    let root = Path::new("tests/fixtures/exports/node_modules/subpath-only");
+
+    assert!(matches!(try_get_package_info("subpath-only", fixture("tests/fixtures/exports")), Err(PackageError::MissingEntry { .. })));
+    assert_eq!(get_package_export("subpath-only", "./lib/x", &[], &options), Some(root.join("lib/x.mjs")));
+    assert_eq!(get_package_export("subpath-only", "./lib/x", &["require"], &options), Some(root.join("lib/x.js")));
+    assert!(matches!(try_get_package_export("subpath-only", ".", &[], &options), Err(PackageError::SubpathNotExported { .. })));
+    assert_eq!(required_conditions_for("subpath-only", "lib/x", &options), vec![vec!["import"], vec!["require"]]);
+}
+
+#[test]
+fn it_should_keep_workspace_packages_without_entry() {
+    let options = fixture("tests/fixtures/monorepo");
+    let names = |packages: Vec<PackageInfo>| -> Vec<String> { packages.into_iter().map(|pkg| pkg.name).collect() };
+    let owner = owning_workspace_package(Path::new("tests/fixtures/monorepo/packages/app/src/main.ts"), &options);
+
+    assert_eq!(names(get_workspace_packages(&options)), vec!["app", "lib"]);
+    assert_eq!(names(workspace_build_order(&options).unwrap()), vec!["lib", "app"]);
+    assert_eq!(owner.map(|pkg| pkg.name), Some(String::from("app")));
+}